@@ -34,7 +34,7 @@ pub trait Phenovalue {
 
 // archerion/mod.rs - The complete pattern
 
-use complexity_validator::ComplexityBound;
+use complexity_validator::{ComplexityBound, LogN};
 
 /// Archerion: Complete observer-consumer pattern
 pub trait Archerion: ComplexityBound<LogN> {
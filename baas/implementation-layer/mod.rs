@@ -1,5 +1,10 @@
 // baas/implementation-layer/mod.rs
 
+use std::time::Duration;
+
+use complexity_validator::{AllocationProbe, AuxSpaceGuard, Complexity, Estimate, Estimator};
+use dag_engine::{AuxSpaceViolation, DepthViolation};
+
 /// Implementation must match design protocol
 pub trait Implementation: DesignProtocol {
     type Execution;
@@ -8,6 +13,63 @@ pub trait Implementation: DesignProtocol {
     /// Execute with O(log n) guarantee
     fn execute(&self) -> Result<Self::Execution, DegradationError>;
     
+    /// `execute` under an `AuxSpaceGuard` allowing `budget(n)` bytes
+    ///
+    /// Only measures when `CountingAlloc` is the global allocator.
+    fn execute_within<B>(&self, n: usize, budget: B) -> Result<Self::Execution, DegradationError>
+    where
+        B: Fn(usize) -> usize,
+    {
+        AuxSpaceGuard::scope("execute", n, budget, || self.execute())?
+    }
+    
+    /// Measured counterpart of `DegradationError::check_complexity`
+    ///
+    /// `factory(n)` builds an implementation for input size `n`; `execute` is
+    /// run across the estimator's sizes. The fitted time class is checked
+    /// against `solution().complexity()` and the fitted space class against
+    /// `solution().space_complexity()`, each only when its fit is at least
+    /// `min_confidence` confident. Space read through `NoProbe`, or without
+    /// `CountingAlloc` installed, is all zero and never confident, so it is
+    /// left unchecked rather than passed.
+    fn measure_complexity<F, P>(
+        estimator: &Estimator<P>,
+        min_confidence: f64,
+        mut factory: F,
+    ) -> Result<Estimate, DegradationError>
+    where
+        Self: Sized,
+        F: FnMut(usize) -> Self,
+        P: AllocationProbe,
+    {
+        let mut design = None;
+        let estimate = estimator.run(
+            |size| {
+                let implementation = factory(size);
+                design.get_or_insert_with(|| {
+                    let solution = implementation.solution();
+                    (solution.complexity(), solution.space_complexity())
+                });
+                implementation
+            },
+            |implementation| implementation.execute(),
+        )?;
+
+        let (time, space) = design.expect("the estimator runs at least one size");
+        if let Some(measured) = estimate.time.confident(min_confidence) {
+            DegradationError::check_complexity(time, measured.clone())?;
+        }
+        if let Some(measured) = estimate.space.confident(min_confidence) {
+            if !measured.is_at_most(&space) {
+                return Err(DegradationError::SpaceMismatch {
+                    design: space,
+                    implementation: measured.clone(),
+                });
+            }
+        }
+        Ok(estimate)
+    }
+    
     /// Load phenomemory token types for validation
     fn load_pheno_tests(&self) -> Self::PhenoTokens;
     
@@ -33,6 +95,11 @@ pub enum DegradationError {
         design: Complexity,
         implementation: Complexity,
     },
+    /// Measured auxiliary space grows past the declared space class
+    SpaceMismatch {
+        design: Complexity,
+        implementation: Complexity,
+    },
     ClassicDegradation {
         cause: String,
         stack_trace: StackTrace,
@@ -41,13 +108,63 @@ pub enum DegradationError {
         entanglement_broken: bool,
         coherence_lost: f64,
     },
+    /// `violation` is set when the limit was found structurally, before any timeout
     FragileDesign {
         timeout: Duration,
         expected_complexity: Complexity,
         actual_complexity: Complexity,
+        violation: Option<ResourceViolation>,
     },
     SilentSegmentFault {
         address: usize,
         instruction: String,
     },
 }
+
+impl DegradationError {
+    /// `ComplexityMismatch` unless `implementation` is within `design` in the lattice order
+    ///
+    /// Incomparable bounds (e.g. O(V) against O(E)) count as a mismatch.
+    pub fn check_complexity(design: Complexity, implementation: Complexity) -> Result<(), Self> {
+        if implementation <= design {
+            return Ok(());
+        }
+        Err(DegradationError::ComplexityMismatch {
+            design,
+            implementation,
+        })
+    }
+
+    /// `FragileDesign` found before any timeout: O(log n) was expected, and
+    /// a chain or stack past that bound can grow to O(n)
+    fn structural(violation: ResourceViolation) -> Self {
+        DegradationError::FragileDesign {
+            timeout: Duration::ZERO,
+            expected_complexity: Complexity::LogN,
+            actual_complexity: Complexity::Linear,
+            violation: Some(violation),
+        }
+    }
+}
+
+/// Structural limit an execution ran past
+///
+/// Depth or auxiliary space beyond O(log n) means the design is fragile.
+pub enum ResourceViolation {
+    /// Chain deeper than the depth policy allows
+    Depth(DepthViolation),
+    /// Traversal stack or heap past its budget
+    AuxSpace(AuxSpaceViolation),
+}
+
+impl From<DepthViolation> for DegradationError {
+    fn from(violation: DepthViolation) -> Self {
+        DegradationError::structural(ResourceViolation::Depth(violation))
+    }
+}
+
+impl From<AuxSpaceViolation> for DegradationError {
+    fn from(violation: AuxSpaceViolation) -> Self {
+        DegradationError::structural(ResourceViolation::AuxSpace(violation))
+    }
+}
@@ -0,0 +1,386 @@
+// core/complexity-validator/estimate/mod.rs
+
+use std::time::{Duration, Instant};
+
+use super::{Bound, Complexity};
+
+/// Source of peak auxiliary allocation during a measured call
+pub trait AllocationProbe {
+    /// Start a new measurement window
+    fn reset(&self);
+
+    /// Peak bytes allocated and still live since the last `reset`
+    fn peak(&self) -> usize;
+}
+
+/// Probe for builds without an allocation counter: always reports zero
+#[derive(Debug, Clone, Copy, Default)]
+pub struct NoProbe;
+
+impl AllocationProbe for NoProbe {
+    fn reset(&self) {}
+
+    fn peak(&self) -> usize {
+        0
+    }
+}
+
+/// Measurements at one input size
+#[derive(Debug, Clone, PartialEq)]
+pub struct Sample {
+    pub size: usize,
+    /// Fastest of the repetitions
+    pub time: Duration,
+    /// Largest peak of the repetitions
+    pub peak_bytes: usize,
+}
+
+/// Best-fitting class for one measured quantity
+#[derive(Debug, Clone, PartialEq)]
+pub struct Fit {
+    pub complexity: Complexity,
+    /// 0 when the runner-up fits as well or every reading was zero, 1 when
+    /// the runner-up fits infinitely worse
+    pub confidence: f64,
+    /// Mean squared log-residual of the best fit
+    pub residual: f64,
+}
+
+impl Fit {
+    /// The fitted class, if the fit is at least `threshold` confident
+    ///
+    /// A fit with zero confidence is never returned, whatever the threshold.
+    pub fn confident(&self, threshold: f64) -> Option<&Complexity> {
+        (self.confidence > 0.0 && self.confidence >= threshold).then_some(&self.complexity)
+    }
+}
+
+/// Fitted time and space classes with the samples behind them
+#[derive(Debug, Clone, PartialEq)]
+pub struct Estimate {
+    pub samples: Vec<Sample>,
+    pub time: Fit,
+    pub space: Fit,
+}
+
+/// Runs a computation over geometrically growing sizes and fits its growth
+///
+/// Each candidate `g` is fitted as `log y = c + log g(n)`; the class with the
+/// least squared residual wins. Only classes in the single variable `n` are
+/// candidates.
+#[derive(Debug, Clone)]
+pub struct Estimator<P = NoProbe> {
+    sizes: Vec<usize>,
+    repetitions: usize,
+    candidates: Vec<Complexity>,
+    probe: P,
+}
+
+impl Estimator<NoProbe> {
+    /// Sizes 256 · 2^k for k < 8, 5 repetitions, every named class, no probe
+    pub fn new() -> Self {
+        Estimator {
+            sizes: Vec::new(),
+            repetitions: 5,
+            candidates: vec![
+                Complexity::Constant,
+                Complexity::LogN,
+                Complexity::PolyLogN(2),
+                Complexity::SqrtN,
+                Complexity::Linear,
+                Complexity::NLogN,
+                Complexity::Polynomial(2),
+                Complexity::Polynomial(3),
+                Complexity::Exponential,
+            ],
+            probe: NoProbe,
+        }
+        .sizes(256, 2, 8)
+    }
+}
+
+impl Default for Estimator<NoProbe> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<P: AllocationProbe> Estimator<P> {
+    /// `count` sizes starting at `start`, each `factor` times the last
+    pub fn sizes(mut self, start: usize, factor: usize, count: usize) -> Self {
+        assert!(start > 0 && factor > 1 && count > 1, "sizes must grow");
+        self.sizes = std::iter::successors(Some(start), |size| size.checked_mul(factor))
+            .take(count)
+            .collect();
+        self
+    }
+
+    /// Runs per size; the fastest time and the largest peak are kept
+    pub fn repetitions(mut self, repetitions: usize) -> Self {
+        self.repetitions = repetitions.max(1);
+        self
+    }
+
+    /// Classes to fit against; ones not purely in `n` are ignored
+    pub fn candidates(mut self, candidates: Vec<Complexity>) -> Self {
+        self.candidates = candidates;
+        self
+    }
+
+    /// Measure peak allocation through `probe`
+    pub fn with_probe<Q: AllocationProbe>(self, probe: Q) -> Estimator<Q> {
+        Estimator {
+            sizes: self.sizes,
+            repetitions: self.repetitions,
+            candidates: self.candidates,
+            probe,
+        }
+    }
+
+    /// Build an input with `factory(n)` per size, time `execute` on it, and fit
+    ///
+    /// The factory runs outside the measured window. The first error from
+    /// `execute` aborts the run.
+    pub fn run<I, R, E, F, X>(&self, mut factory: F, mut execute: X) -> Result<Estimate, E>
+    where
+        F: FnMut(usize) -> I,
+        X: FnMut(&I) -> Result<R, E>,
+    {
+        let mut samples = Vec::with_capacity(self.sizes.len());
+        for &size in &self.sizes {
+            let input = factory(size);
+            let mut sample = Sample {
+                size,
+                time: Duration::MAX,
+                peak_bytes: 0,
+            };
+
+            for _ in 0..self.repetitions {
+                self.probe.reset();
+                let start = Instant::now();
+                let output = execute(&input)?;
+                let elapsed = start.elapsed();
+                sample.peak_bytes = sample.peak_bytes.max(self.probe.peak());
+                sample.time = sample.time.min(elapsed);
+                drop(output); // Outside the window: only the computation is timed
+            }
+            samples.push(sample);
+        }
+
+        let time: Vec<f64> = samples.iter().map(|s| s.time.as_secs_f64()).collect();
+        let space: Vec<f64> = samples.iter().map(|s| s.peak_bytes as f64).collect();
+        Ok(Estimate {
+            time: self.fit(&samples, &time),
+            space: self.fit(&samples, &space),
+            samples,
+        })
+    }
+
+    /// Least-squares fit of `values` against every candidate
+    fn fit(&self, samples: &[Sample], values: &[f64]) -> Fit {
+        // Zero readings (no allocation, timer granularity) are raised to the
+        // smallest positive one, capped at one unit, so their log is finite
+        let floor = values
+            .iter()
+            .copied()
+            .filter(|value| *value > 0.0)
+            .fold(1.0, f64::min);
+        let logs: Vec<f64> = values.iter().map(|value| value.max(floor).ln()).collect();
+
+        let mut fits: Vec<(f64, &Complexity)> = self
+            .candidates
+            .iter()
+            .filter_map(|candidate| {
+                let bound = candidate.bound();
+                let model: Option<Vec<f64>> = samples
+                    .iter()
+                    .map(|sample| ln_growth(&bound, sample.size as f64))
+                    .collect();
+                Some((residual(&logs, &model?), candidate))
+            })
+            .collect();
+        fits.sort_by(|a, b| a.0.total_cmp(&b.0));
+
+        let Some(&(best, complexity)) = fits.first() else {
+            return Fit {
+                complexity: Complexity::Constant,
+                confidence: 0.0,
+                residual: f64::INFINITY,
+            };
+        };
+        // All-zero readings (`NoProbe`, no counting allocator) say nothing about growth
+        let measured = values.iter().any(|value| *value > 0.0);
+        let confidence = match fits.get(1) {
+            _ if !measured => 0.0,
+            None => 1.0,
+            Some(&(runner_up, _)) if runner_up > 0.0 => 1.0 - best / runner_up,
+            Some(_) => 0.0,
+        };
+        Fit {
+            complexity: complexity.clone(),
+            confidence,
+            residual: best,
+        }
+    }
+}
+
+/// Mean squared residual of `logs - model` once the best constant is removed
+fn residual(logs: &[f64], model: &[f64]) -> f64 {
+    let offsets: Vec<f64> = logs.iter().zip(model).map(|(y, g)| y - g).collect();
+    let mean = offsets.iter().sum::<f64>() / offsets.len() as f64;
+    offsets
+        .iter()
+        .map(|offset| (offset - mean).powi(2))
+        .sum::<f64>()
+        / offsets.len() as f64
+}
+
+/// `ln g(n)` for a bound in `n` alone; `None` for any other variable
+///
+/// Logs are base 2 and at least 1, so `log n` stays positive for small `n`.
+fn ln_growth(bound: &Bound, n: f64) -> Option<f64> {
+    let ln_log = n.log2().max(1.0).ln();
+    let terms: Option<Vec<f64>> = bound
+        .terms
+        .iter()
+        .map(|term| {
+            term.factors.iter().try_fold(0.0, |total, (name, growth)| {
+                (name == "n").then(|| {
+                    let power = f64::from(growth.power.num) / f64::from(growth.power.den);
+                    total
+                        + f64::from(growth.exponential) * n * std::f64::consts::LN_2
+                        + power * n.ln()
+                        + f64::from(growth.log) * ln_log
+                })
+            })
+        })
+        .collect();
+
+    // ln of a sum of terms, shifted by the largest to stay finite
+    let terms = terms?;
+    let largest = terms.iter().copied().fold(f64::NEG_INFINITY, f64::max);
+    Some(largest + terms.iter().map(|t| (t - largest).exp()).sum::<f64>().ln())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::cell::Cell;
+
+    /// Samples at 256 · 2^k whose readings follow `value(n)`
+    fn fit_of(value: impl Fn(f64) -> f64) -> Fit {
+        let estimator = Estimator::new();
+        let samples: Vec<Sample> = estimator
+            .sizes
+            .iter()
+            .map(|&size| Sample {
+                size,
+                time: Duration::ZERO,
+                peak_bytes: 0,
+            })
+            .collect();
+        let values: Vec<f64> = samples.iter().map(|s| value(s.size as f64)).collect();
+        estimator.fit(&samples, &values)
+    }
+
+    #[test]
+    fn exact_growth_is_recovered() {
+        assert_eq!(fit_of(|_| 7.0).complexity, Complexity::Constant);
+        assert_eq!(fit_of(|n| 3.0 * n.log2()).complexity, Complexity::LogN);
+        assert_eq!(fit_of(|n| n / 2.0).complexity, Complexity::Linear);
+        assert_eq!(fit_of(|n| n * n.log2()).complexity, Complexity::NLogN);
+        assert_eq!(fit_of(|n| n * n).complexity, Complexity::Polynomial(2));
+
+        let linear = fit_of(|n| 5.0 * n);
+        assert!(linear.residual < 1e-12);
+        assert!(linear.confidence > 0.99);
+    }
+
+    #[test]
+    fn confident_gates_on_the_threshold() {
+        let fit = Fit {
+            complexity: Complexity::Linear,
+            confidence: 0.4,
+            residual: 0.1,
+        };
+        assert_eq!(fit.confident(0.3), Some(&Complexity::Linear));
+        assert_eq!(fit.confident(0.5), None);
+    }
+
+    #[test]
+    fn no_candidates_gives_no_confidence() {
+        let estimator = Estimator::new().candidates(vec![Complexity::var("V").unwrap()]);
+        let samples = [Sample {
+            size: 1,
+            time: Duration::ZERO,
+            peak_bytes: 0,
+        }];
+        let fit = estimator.fit(&samples, &[1.0]);
+        assert_eq!((fit.confidence, fit.residual), (0.0, f64::INFINITY));
+        assert_eq!(fit.confident(0.5), None);
+    }
+
+    /// Probe reporting whatever the measured call last stored
+    struct Scripted<'a>(&'a Cell<usize>);
+
+    impl AllocationProbe for Scripted<'_> {
+        fn reset(&self) {
+            self.0.set(0);
+        }
+
+        fn peak(&self) -> usize {
+            self.0.get()
+        }
+    }
+
+    #[test]
+    fn run_fits_the_probed_space() {
+        let peak = Cell::new(0);
+        let estimator = Estimator::new()
+            .sizes(64, 2, 6)
+            .repetitions(2)
+            .with_probe(Scripted(&peak));
+
+        let estimate = estimator
+            .run(
+                |size| size,
+                |&size| {
+                    peak.set(size * 8);
+                    Ok::<_, ()>(())
+                },
+            )
+            .unwrap();
+        assert_eq!(estimate.samples.len(), 6);
+        assert_eq!(estimate.samples[5].peak_bytes, 64 * 32 * 8);
+        assert_eq!(estimate.space.complexity, Complexity::Linear);
+    }
+
+    #[test]
+    fn unprobed_space_gives_no_confidence() {
+        let estimate = Estimator::new()
+            .sizes(64, 2, 4)
+            .run(|size| vec![0u8; size], |input| Ok::<_, ()>(input.len()))
+            .unwrap();
+        assert_eq!(estimate.space.confidence, 0.0);
+        assert_eq!(estimate.space.confident(0.0), None);
+        assert_eq!(fit_of(|_| 0.0).confidence, 0.0);
+    }
+
+    #[test]
+    fn run_stops_at_the_first_error() {
+        let mut calls = 0;
+        let result = Estimator::new().run(
+            |size| size,
+            |&size| {
+                calls += 1;
+                if size > 256 {
+                    Err(size)
+                } else {
+                    Ok(())
+                }
+            },
+        );
+        assert_eq!(result, Err(512));
+        assert_eq!(calls, 6);
+    }
+}
@@ -0,0 +1,252 @@
+// core/complexity-validator/marker/mod.rs
+
+use super::Complexity;
+
+/// Zero-sized complexity class usable as a type parameter
+pub trait Class {
+    /// Runtime value of this class in the `Complexity` lattice
+    fn complexity() -> Complexity;
+}
+
+/// `Self` grows no faster than `C`
+pub trait AtMost<C: Class>: Class {}
+
+/// Least class covering `Self` and `C` (sequential composition)
+pub trait MaxWith<C: Class>: Class {
+    type Output: Class;
+}
+
+/// Class of `Self` work per step of `C` (nested composition)
+pub trait ProductWith<C: Class>: Class {
+    type Output: Class;
+}
+
+/// `max(A, B)`: cost of running `A` then `B`
+pub type Max<A, B> = <A as MaxWith<B>>::Output;
+
+/// `A · B`: cost of running `A` inside each step of `B`
+pub type Product<A, B> = <A as ProductWith<B>>::Output;
+
+/// Component with a declared complexity class
+pub trait Bounded {
+    type Class: Class;
+}
+
+/// Component proven at compile time to stay within class `C`
+///
+/// Implemented for every `Bounded` type whose class is `AtMost<C>`, so an
+/// O(n) component in an O(log n) slot is a type error:
+///
+/// ```compile_fail
+/// use complexity_validator::{ComplexityBound, Linear, LogN};
+///
+/// fn log_slot<T: ComplexityBound<LogN>>() {}
+/// log_slot::<Linear>();
+/// ```
+///
+/// Nesting multiplies classes, so an O(log n) step per element fits O(n log n):
+///
+/// ```
+/// use complexity_validator::{ComplexityBound, Linear, LogN, NLogN, Nested};
+///
+/// fn n_log_n_slot<T: ComplexityBound<NLogN>>() {}
+/// n_log_n_slot::<Nested<Linear, LogN>>();
+/// ```
+pub trait ComplexityBound<C: Class> {}
+
+impl<T, C> ComplexityBound<C> for T
+where
+    T: Bounded,
+    T::Class: AtMost<C>,
+    C: Class,
+{
+}
+
+/// Run `A`, then `B`
+pub struct Sequence<A, B>(pub A, pub B);
+
+impl<A, B> Bounded for Sequence<A, B>
+where
+    A: Bounded,
+    B: Bounded,
+    A::Class: MaxWith<B::Class>,
+{
+    type Class = Max<A::Class, B::Class>;
+}
+
+/// Run `Inner` once per step of `Outer`
+pub struct Nested<Outer, Inner>(pub Outer, pub Inner);
+
+impl<Outer, Inner> Bounded for Nested<Outer, Inner>
+where
+    Outer: Bounded,
+    Inner: Bounded,
+    Inner::Class: ProductWith<Outer::Class>,
+{
+    type Class = Product<Inner::Class, Outer::Class>;
+}
+
+/// Runtime class of a bounded component
+pub fn declared<T: Bounded>() -> Complexity {
+    T::Class::complexity()
+}
+
+macro_rules! classes {
+    ($($(#[$doc:meta])* $marker:ident => $complexity:expr),* $(,)?) => {
+        $(
+            $(#[$doc])*
+            #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+            pub struct $marker;
+
+            impl Class for $marker {
+                fn complexity() -> Complexity {
+                    $complexity
+                }
+            }
+
+            /// A marker stands for a component of its own class
+            impl Bounded for $marker {
+                type Class = $marker;
+            }
+        )*
+    };
+}
+
+/// `AtMost` and `MaxWith` for every pair of a chain listed slowest first
+macro_rules! chain {
+    () => {};
+    ($head:ident $(, $tail:ident)*) => {
+        impl AtMost<$head> for $head {}
+        impl MaxWith<$head> for $head {
+            type Output = $head;
+        }
+        $(
+            impl AtMost<$tail> for $head {}
+            impl MaxWith<$tail> for $head {
+                type Output = $tail;
+            }
+            impl MaxWith<$head> for $tail {
+                type Output = $tail;
+            }
+        )*
+        chain!($($tail),*);
+    };
+}
+
+/// `ProductWith` for each listed product that is again a modelled class
+macro_rules! products {
+    ($($left:ident * $right:ident = $output:ident),* $(,)?) => {
+        $(
+            impl ProductWith<$right> for $left {
+                type Output = $output;
+            }
+        )*
+    };
+}
+
+classes! {
+    /// O(1)
+    Const => Complexity::Constant,
+    /// O(log n)
+    LogN => Complexity::LogN,
+    /// O(log² n)
+    LogSquaredN => Complexity::PolyLogN(2),
+    /// O(sqrt n)
+    SqrtN => Complexity::SqrtN,
+    /// O(n)
+    Linear => Complexity::Linear,
+    /// O(n log n)
+    NLogN => Complexity::NLogN,
+    /// O(n²)
+    Quadratic => Complexity::Polynomial(2),
+    /// O(n³)
+    Cubic => Complexity::Polynomial(3),
+    /// O(2^n)
+    Exponential => Complexity::Exponential,
+}
+
+chain!(
+    Const,
+    LogN,
+    LogSquaredN,
+    SqrtN,
+    Linear,
+    NLogN,
+    Quadratic,
+    Cubic,
+    Exponential
+);
+
+products! {
+    Const * Const = Const,
+    Const * LogN = LogN,
+    Const * LogSquaredN = LogSquaredN,
+    Const * SqrtN = SqrtN,
+    Const * Linear = Linear,
+    Const * NLogN = NLogN,
+    Const * Quadratic = Quadratic,
+    Const * Cubic = Cubic,
+    Const * Exponential = Exponential,
+    LogN * Const = LogN,
+    LogSquaredN * Const = LogSquaredN,
+    SqrtN * Const = SqrtN,
+    Linear * Const = Linear,
+    NLogN * Const = NLogN,
+    Quadratic * Const = Quadratic,
+    Cubic * Const = Cubic,
+    Exponential * Const = Exponential,
+    LogN * LogN = LogSquaredN,
+    LogN * Linear = NLogN,
+    Linear * LogN = NLogN,
+    SqrtN * SqrtN = Linear,
+    Linear * Linear = Quadratic,
+    Linear * Quadratic = Cubic,
+    Quadratic * Linear = Cubic,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Compiles only when `T` fits `C`
+    fn fits<T: ComplexityBound<C>, C: Class>() {}
+
+    #[test]
+    fn markers_match_their_runtime_class() {
+        assert_eq!(declared::<Const>(), Complexity::Constant);
+        assert_eq!(declared::<LogSquaredN>(), Complexity::PolyLogN(2));
+        assert_eq!(declared::<Cubic>(), Complexity::Polynomial(3));
+    }
+
+    #[test]
+    fn sequence_takes_the_slower_class() {
+        assert_eq!(declared::<Sequence<LogN, Linear>>(), Complexity::Linear);
+        assert_eq!(
+            declared::<Sequence<Quadratic, SqrtN>>(),
+            Complexity::Polynomial(2)
+        );
+        fits::<Sequence<Const, LogN>, LogN>();
+    }
+
+    #[test]
+    fn nesting_multiplies_classes() {
+        assert_eq!(declared::<Nested<Linear, LogN>>(), Complexity::NLogN);
+        assert_eq!(declared::<Nested<LogN, LogN>>(), Complexity::PolyLogN(2));
+        assert_eq!(declared::<Nested<SqrtN, SqrtN>>(), Complexity::Linear);
+        assert_eq!(
+            declared::<Nested<Linear, Nested<Linear, Linear>>>(),
+            Complexity::Polynomial(3)
+        );
+        fits::<Nested<Linear, LogN>, Quadratic>();
+    }
+
+    #[test]
+    fn type_level_order_agrees_with_the_lattice() {
+        fn at_most<A: AtMost<B>, B: Class>() -> bool {
+            A::complexity() <= B::complexity()
+        }
+        assert!(at_most::<Const, Exponential>());
+        assert!(at_most::<LogSquaredN, SqrtN>());
+        assert!(at_most::<NLogN, NLogN>());
+    }
+}
@@ -0,0 +1,593 @@
+// core/complexity-validator/mod.rs
+
+use std::cmp::Ordering;
+use std::collections::{BTreeMap, BTreeSet};
+use std::fmt;
+use std::hash::{Hash, Hasher};
+use std::ops::{Add, Mul};
+
+pub mod estimate;
+pub mod marker;
+pub mod parse;
+pub mod tracking;
+
+pub use estimate::{AllocationProbe, Estimate, Estimator, Fit, NoProbe, Sample};
+pub use marker::{
+    declared, AtMost, Bounded, Class, ComplexityBound, Const, Cubic, Exponential, Linear, LogN,
+    LogSquaredN, Max, MaxWith, NLogN, Nested, Product, ProductWith, Quadratic, Sequence, SqrtN,
+};
+pub use parse::ParseError;
+pub use tracking::{log_budget, AuxSpaceGuard, CountingAlloc};
+
+/// Asymptotic complexity class
+///
+/// Named variants are in `n`; anything else is `General`. Equality, order and
+/// hashing compare the normalized bound, so `General(n)` equals `Linear`.
+#[derive(Debug, Clone)]
+pub enum Complexity {
+    /// O(1)
+    Constant,
+    /// O(log n)
+    LogN,
+    /// O(log^k n)
+    PolyLogN(u32),
+    /// O(sqrt n)
+    SqrtN,
+    /// O(n)
+    Linear,
+    /// O(n log n)
+    NLogN,
+    /// O(n^k)
+    Polynomial(u32),
+    /// O(2^n)
+    Exponential,
+    /// Any other bound, including multivariate ones such as O(V + E)
+    General(Bound),
+}
+
+/// Normal form of a complexity: a sum of monomials, none dominated by another
+///
+/// `O(f + g) = O(max(f, g))`, so a dominated term is dropped; terms that are
+/// incomparable (e.g. `V` and `E`) are both kept.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Bound {
+    terms: BTreeSet<Term>,
+}
+
+/// Product over variables of their growth; the empty product is O(1)
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash, PartialOrd, Ord)]
+struct Term {
+    factors: BTreeMap<String, Growth>, // Only non-constant factors
+}
+
+/// `2^(exponential · x) · x^power · log^log x` for one variable `x`
+///
+/// Field order is asymptotic order, so the derived `Ord` compares growth.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, PartialOrd, Ord)]
+struct Growth {
+    exponential: u32,
+    power: Ratio,
+    log: u32,
+}
+
+/// Non-negative fraction in lowest terms
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+struct Ratio {
+    num: u32,
+    den: u32,
+}
+
+/// Why `Bound::pow` has no result
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum PowError {
+    /// A log or exponential exponent would be fractional
+    Fractional,
+    /// An exponent would not fit in `u32`
+    Overflow,
+}
+
+impl Complexity {
+    /// O(x) for a named input size `x`, e.g. `V` or `E`
+    ///
+    /// `name` must be an identifier the parser reads back as a variable: a
+    /// letter followed by letters, digits or `_`, other than `log`, `sqrt`
+    /// and `O`.
+    pub fn var(name: &str) -> Result<Self, ParseError> {
+        parse::check_variable(name)?;
+        Ok(Complexity::from(Bound::single(
+            name,
+            Growth {
+                power: Ratio::ONE,
+                ..Growth::default()
+            },
+        )))
+    }
+
+    /// Normal form used for comparison and arithmetic
+    pub fn bound(&self) -> Bound {
+        let growth = |exponential, power, log| Growth {
+            exponential,
+            power,
+            log,
+        };
+        let n = |g| Bound::single("n", g);
+
+        match self {
+            Complexity::Constant => Bound::constant(),
+            Complexity::LogN => n(growth(0, Ratio::ZERO, 1)),
+            Complexity::PolyLogN(k) => n(growth(0, Ratio::ZERO, *k)),
+            Complexity::SqrtN => n(growth(0, Ratio::new(1, 2), 0)),
+            Complexity::Linear => n(growth(0, Ratio::ONE, 0)),
+            Complexity::NLogN => n(growth(0, Ratio::ONE, 1)),
+            Complexity::Polynomial(k) => n(growth(0, Ratio::new(*k, 1), 0)),
+            Complexity::Exponential => n(growth(1, Ratio::ZERO, 0)),
+            Complexity::General(bound) => bound.clone(),
+        }
+    }
+
+    /// True when `self` grows no faster than `other`
+    pub fn is_at_most(&self, other: &Complexity) -> bool {
+        self.bound().is_at_most(&other.bound())
+    }
+
+    /// Input size variables this bound depends on
+    pub fn variables(&self) -> BTreeSet<String> {
+        self.bound()
+            .terms
+            .iter()
+            .flat_map(|term| term.factors.keys().cloned())
+            .collect()
+    }
+}
+
+impl From<Bound> for Complexity {
+    /// Canonical form: a named variant when the bound is one, else `General`
+    fn from(bound: Bound) -> Self {
+        let mut terms = bound.terms.iter();
+        let (Some(term), None) = (terms.next(), terms.next()) else {
+            return Complexity::General(bound);
+        };
+
+        let mut factors = term.factors.iter();
+        let growth = match (factors.next(), factors.next()) {
+            (None, _) => return Complexity::Constant,
+            (Some((name, growth)), None) if name == "n" => *growth,
+            _ => return Complexity::General(bound),
+        };
+
+        let Growth {
+            exponential,
+            power,
+            log,
+        } = growth;
+        match (exponential, power.num, power.den, log) {
+            (0, 0, _, 1) => Complexity::LogN,
+            (0, 0, _, k) => Complexity::PolyLogN(k),
+            (0, 1, 2, 0) => Complexity::SqrtN,
+            (0, 1, 1, 0) => Complexity::Linear,
+            (0, 1, 1, 1) => Complexity::NLogN,
+            (0, k, 1, 0) => Complexity::Polynomial(k),
+            (1, 0, _, 0) => Complexity::Exponential,
+            _ => Complexity::General(bound),
+        }
+    }
+}
+
+impl PartialEq for Complexity {
+    fn eq(&self, other: &Self) -> bool {
+        self.bound() == other.bound()
+    }
+}
+
+impl Eq for Complexity {}
+
+impl Hash for Complexity {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.bound().hash(state);
+    }
+}
+
+/// Asymptotic order; `None` when neither bound dominates (e.g. O(V) vs O(E))
+impl PartialOrd for Complexity {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        let (left, right) = (self.bound(), other.bound());
+        match (left.is_at_most(&right), right.is_at_most(&left)) {
+            (true, true) => Some(Ordering::Equal),
+            (true, false) => Some(Ordering::Less),
+            (false, true) => Some(Ordering::Greater),
+            (false, false) => None,
+        }
+    }
+}
+
+/// Sequential composition: the least bound covering both (the max)
+impl Add for Complexity {
+    type Output = Complexity;
+
+    fn add(self, other: Complexity) -> Complexity {
+        Complexity::from(self.bound().add(&other.bound()))
+    }
+}
+
+/// Nested composition: `self` work for each step of `other`
+impl Mul for Complexity {
+    type Output = Complexity;
+
+    fn mul(self, other: Complexity) -> Complexity {
+        Complexity::from(self.bound().mul(&other.bound()))
+    }
+}
+
+impl fmt::Display for Complexity {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "O({})", self.bound())
+    }
+}
+
+impl Bound {
+    /// O(1)
+    fn constant() -> Self {
+        Self::normalize([Term::default()])
+    }
+
+    /// O(growth of `name`)
+    fn single(name: &str, growth: Growth) -> Self {
+        let mut term = Term::default();
+        if growth != Growth::default() {
+            term.factors.insert(name.to_string(), growth);
+        }
+        Self::normalize([term])
+    }
+
+    /// Every term of `self` is dominated by some term of `other`
+    ///
+    /// Terms compare variable by variable, so the order is partial: sound
+    /// for any inputs, but incomparable bounds are never reordered.
+    pub fn is_at_most(&self, other: &Bound) -> bool {
+        self.terms
+            .iter()
+            .all(|term| other.terms.iter().any(|bigger| term.is_at_most(bigger)))
+    }
+
+    fn add(&self, other: &Bound) -> Bound {
+        Self::normalize(self.terms.iter().chain(&other.terms).cloned())
+    }
+
+    /// Product, with exponents that overflow `u32` capped
+    fn mul(&self, other: &Bound) -> Bound {
+        self.mul_with(other, |left, right| Some(left.saturating_mul(right)))
+            .expect("saturating products always exist")
+    }
+
+    /// Product, or `None` when an exponent overflows `u32`
+    fn checked_mul(&self, other: &Bound) -> Option<Bound> {
+        self.mul_with(other, Growth::checked_mul)
+    }
+
+    fn mul_with<F>(&self, other: &Bound, mut combine: F) -> Option<Bound>
+    where
+        F: FnMut(Growth, Growth) -> Option<Growth>,
+    {
+        let mut terms = Vec::with_capacity(self.terms.len() * other.terms.len());
+        for left in &self.terms {
+            for right in &other.terms {
+                terms.push(left.mul_with(right, &mut combine)?);
+            }
+        }
+        Some(Self::normalize(terms))
+    }
+
+    /// `self^ratio`, distributing over the sum; fails unless every log and
+    /// exponential exponent stays integral and every exponent fits
+    fn pow(&self, ratio: Ratio) -> Result<Bound, PowError> {
+        let terms: Result<Vec<Term>, PowError> =
+            self.terms.iter().map(|term| term.pow(ratio)).collect();
+        Ok(Self::normalize(terms?))
+    }
+
+    /// Drop dominated terms, keeping the rest in term order
+    fn normalize<I>(terms: I) -> Bound
+    where
+        I: IntoIterator<Item = Term>,
+    {
+        let candidates: BTreeSet<Term> = terms.into_iter().collect();
+        let terms = candidates
+            .iter()
+            .filter(|term| {
+                !candidates
+                    .iter()
+                    .any(|other| other != *term && term.is_at_most(other))
+            })
+            .cloned()
+            .collect::<BTreeSet<_>>();
+
+        if terms.is_empty() {
+            return Bound {
+                terms: BTreeSet::from([Term::default()]),
+            };
+        }
+        Bound { terms }
+    }
+}
+
+impl fmt::Display for Bound {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (position, term) in self.terms.iter().enumerate() {
+            if position > 0 {
+                f.write_str(" + ")?;
+            }
+            write!(f, "{term}")?;
+        }
+        Ok(())
+    }
+}
+
+impl Term {
+    fn is_at_most(&self, other: &Term) -> bool {
+        self.factors
+            .iter()
+            .all(|(name, growth)| *growth <= other.factors.get(name).copied().unwrap_or_default())
+    }
+
+    /// Product, multiplying shared variables' growths with `combine`
+    fn mul_with<F>(&self, other: &Term, combine: &mut F) -> Option<Term>
+    where
+        F: FnMut(Growth, Growth) -> Option<Growth>,
+    {
+        let mut factors = self.factors.clone();
+        for (name, growth) in &other.factors {
+            let entry = factors.entry(name.clone()).or_default();
+            *entry = combine(*entry, *growth)?;
+        }
+        Some(Term { factors })
+    }
+
+    fn pow(&self, ratio: Ratio) -> Result<Term, PowError> {
+        let mut factors = BTreeMap::new();
+        for (name, growth) in &self.factors {
+            let growth = Growth {
+                exponential: ratio.scale(growth.exponential)?,
+                power: growth.power.checked_mul(ratio).ok_or(PowError::Overflow)?,
+                log: ratio.scale(growth.log)?,
+            };
+            if growth != Growth::default() {
+                factors.insert(name.clone(), growth);
+            }
+        }
+        Ok(Term { factors })
+    }
+}
+
+impl Growth {
+    /// `self · other`, or `None` when an exponent overflows `u32`
+    fn checked_mul(self, other: Growth) -> Option<Growth> {
+        Some(Growth {
+            exponential: self.exponential.checked_add(other.exponential)?,
+            power: self.power.checked_add(other.power)?,
+            log: self.log.checked_add(other.log)?,
+        })
+    }
+
+    /// `self · other` with every exponent capped at `u32::MAX`
+    fn saturating_mul(self, other: Growth) -> Growth {
+        Growth {
+            exponential: self.exponential.saturating_add(other.exponential),
+            power: self.power.saturating_add(other.power),
+            log: self.log.saturating_add(other.log),
+        }
+    }
+}
+
+impl fmt::Display for Term {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.factors.is_empty() {
+            return f.write_str("1");
+        }
+
+        let mut parts = Vec::new();
+        for (name, growth) in &self.factors {
+            match growth.exponential {
+                0 => {}
+                1 => parts.push(format!("2^{name}")),
+                // 2^(c·x) written as (2^c)^x while it fits in u64, else as repeated factors
+                c if c < 64 => parts.push(format!("{}^{name}", 1u64 << c)),
+                c => parts.extend((0..c).map(|_| format!("2^{name}"))),
+            }
+            match growth.power {
+                Ratio::ZERO => {}
+                Ratio::ONE => parts.push(name.clone()),
+                Ratio { num: 1, den: 2 } => parts.push(format!("sqrt {name}")),
+                Ratio { num, den: 1 } => parts.push(format!("{name}^{num}")),
+                Ratio { num, den } => parts.push(format!("{name}^{num}/{den}")),
+            }
+            match growth.log {
+                0 => {}
+                1 => parts.push(format!("log {name}")),
+                k => parts.push(format!("log^{k} {name}")),
+            }
+        }
+        f.write_str(&parts.join(" "))
+    }
+}
+
+impl Ratio {
+    const ZERO: Ratio = Ratio { num: 0, den: 1 };
+    const ONE: Ratio = Ratio { num: 1, den: 1 };
+
+    fn new(num: u32, den: u32) -> Self {
+        assert!(den > 0, "zero denominator");
+        Ratio::wide(u64::from(num), u64::from(den)).expect("reducing never grows a fraction")
+    }
+
+    /// `num / den` in lowest terms, or `None` when it does not fit in `u32`s
+    fn wide(num: u64, den: u64) -> Option<Ratio> {
+        let divisor = gcd(num, den);
+        Some(Ratio {
+            num: u32::try_from(num / divisor).ok()?,
+            den: u32::try_from(den / divisor).ok()?,
+        })
+    }
+
+    /// `self + other` as `(num, den)` before reduction
+    fn sum(self, other: Ratio) -> (u64, u64) {
+        let (a, b) = (u64::from(self.num), u64::from(self.den));
+        let (c, d) = (u64::from(other.num), u64::from(other.den));
+        (a * d + c * b, b * d)
+    }
+
+    fn checked_add(self, other: Ratio) -> Option<Ratio> {
+        let (num, den) = self.sum(other);
+        Ratio::wide(num, den)
+    }
+
+    /// `self + other`, rounded to a nearby fraction when it does not fit
+    fn saturating_add(self, other: Ratio) -> Ratio {
+        let (mut num, mut den) = self.sum(other);
+        let divisor = gcd(num, den);
+        (num, den) = (num / divisor, den / divisor);
+        while num > u64::from(u32::MAX) || den > u64::from(u32::MAX) {
+            (num, den) = ((num >> 1).max(1), (den >> 1).max(1));
+        }
+        Ratio::new(num as u32, den as u32)
+    }
+
+    fn checked_mul(self, other: Ratio) -> Option<Ratio> {
+        Ratio::wide(
+            u64::from(self.num) * u64::from(other.num),
+            u64::from(self.den) * u64::from(other.den),
+        )
+    }
+
+    /// `value · self` when it is a whole number that fits in `u32`
+    fn scale(self, value: u32) -> Result<u32, PowError> {
+        let product = u64::from(value) * u64::from(self.num);
+        if !product.is_multiple_of(u64::from(self.den)) {
+            return Err(PowError::Fractional);
+        }
+        u32::try_from(product / u64::from(self.den)).map_err(|_| PowError::Overflow)
+    }
+}
+
+impl Default for Ratio {
+    fn default() -> Self {
+        Ratio::ZERO
+    }
+}
+
+impl Ord for Ratio {
+    fn cmp(&self, other: &Self) -> Ordering {
+        (u64::from(self.num) * u64::from(other.den))
+            .cmp(&(u64::from(other.num) * u64::from(self.den)))
+    }
+}
+
+impl PartialOrd for Ratio {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+fn gcd(mut a: u64, mut b: u64) -> u64 {
+    while b != 0 {
+        (a, b) = (b, a % b);
+    }
+    a.max(1)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(text: &str) -> Complexity {
+        text.parse().unwrap()
+    }
+
+    #[test]
+    fn named_classes_are_totally_ordered() {
+        let chain = [
+            Complexity::Constant,
+            Complexity::LogN,
+            Complexity::PolyLogN(2),
+            Complexity::SqrtN,
+            Complexity::Linear,
+            Complexity::NLogN,
+            Complexity::Polynomial(2),
+            Complexity::Polynomial(3),
+            Complexity::Exponential,
+        ];
+        for pair in chain.windows(2) {
+            assert!(pair[0] < pair[1], "{} < {}", pair[0], pair[1]);
+            assert!(pair[0].is_at_most(&pair[1]) && !pair[1].is_at_most(&pair[0]));
+        }
+    }
+
+    #[test]
+    fn general_bounds_normalize_to_named_variants() {
+        assert_eq!(
+            Complexity::General(Complexity::Linear.bound()),
+            Complexity::Linear
+        );
+        assert!(matches!(
+            Complexity::from(Complexity::Polynomial(1).bound()),
+            Complexity::Linear
+        ));
+        assert!(matches!(
+            Complexity::from(Complexity::PolyLogN(1).bound()),
+            Complexity::LogN
+        ));
+    }
+
+    #[test]
+    fn separate_variables_are_incomparable() {
+        let (v, e) = (Complexity::var("V").unwrap(), Complexity::var("E").unwrap());
+        assert_eq!(v.partial_cmp(&e), None);
+
+        let sum = v.clone() + e.clone();
+        assert!(v <= sum && e <= sum);
+        assert_eq!(sum.variables(), BTreeSet::from(["E".into(), "V".into()]));
+        assert_eq!(sum.to_string(), "O(E + V)");
+    }
+
+    #[test]
+    fn composition_takes_the_max_or_the_product() {
+        assert_eq!(Complexity::Linear + Complexity::LogN, Complexity::Linear);
+        assert_eq!(Complexity::Linear * Complexity::LogN, Complexity::NLogN);
+        assert_eq!(Complexity::SqrtN * Complexity::SqrtN, Complexity::Linear);
+        assert_eq!(
+            Complexity::Polynomial(2) * Complexity::Linear,
+            Complexity::Polynomial(3)
+        );
+        assert_eq!(
+            (Complexity::var("V").unwrap() + Complexity::var("E").unwrap()) * Complexity::Constant,
+            parse("V + E")
+        );
+    }
+
+    #[test]
+    fn products_saturate_instead_of_overflowing() {
+        let huge = Complexity::PolyLogN(u32::MAX - 1);
+        assert_eq!(huge.clone() * huge, Complexity::PolyLogN(u32::MAX));
+
+        let odd = parse("n^1/4294967291");
+        let product = odd.clone() * parse("n^1/4294967279");
+        assert!(odd < product && product < Complexity::Linear);
+    }
+
+    #[test]
+    fn variables_display_in_a_form_that_parses_back() {
+        for name in ["n", "V", "E", "x1", "node_count", "logs", "On", "λ"] {
+            let single = Complexity::var(name).unwrap();
+            assert_eq!(parse(&single.to_string()), single, "{name}");
+
+            let mixed = single.clone() * Complexity::var("m").unwrap() + Complexity::NLogN;
+            assert_eq!(parse(&mixed.to_string()), mixed, "{name}");
+        }
+    }
+
+    #[test]
+    fn reserved_words_and_non_identifiers_are_not_variables() {
+        for name in ["log", "sqrt", "O", "", "2n", "a-b", "n m", "√n", "_n"] {
+            assert!(Complexity::var(name).is_err(), "{name:?}");
+        }
+    }
+}
@@ -0,0 +1,509 @@
+// core/complexity-validator/parse/mod.rs
+
+use std::fmt;
+use std::str::FromStr;
+
+use super::{Bound, Complexity, Growth, PowError, Ratio};
+
+/// Failure to parse a complexity such as `O(n log n)`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ParseError {
+    /// Input ended where a term was expected
+    UnexpectedEnd,
+    /// Token at character `position` does not fit the grammar
+    Unexpected { position: usize, found: String },
+    /// Power at `position` leaves a log or exponential with a fractional exponent
+    FractionalExponent { position: usize },
+    /// Number or exponent at `position` grows past what a bound can hold
+    Overflow { position: usize },
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ParseError::UnexpectedEnd => write!(f, "complexity ends unexpectedly"),
+            ParseError::Unexpected { position, found } => {
+                write!(f, "unexpected `{found}` at position {position}")
+            }
+            ParseError::FractionalExponent { position } => write!(
+                f,
+                "power at position {position} gives a log or exponential a fractional exponent"
+            ),
+            ParseError::Overflow { position } => {
+                write!(f, "exponent at position {position} is too large")
+            }
+        }
+    }
+}
+
+impl std::error::Error for ParseError {}
+
+/// Parses the form printed by `Display`, with or without the `O(…)` wrapper
+///
+/// Terms are summed with `+` and multiplied by juxtaposition or `*`:
+/// `1`, `n`, `V + E`, `n log n`, `log^2 n`, `sqrt n`, `n^3/2`, `2^n`, `(V + E) log V`.
+/// A power right after `log`'s argument belongs to it: `log n^2` is O(log n).
+impl FromStr for Complexity {
+    type Err = ParseError;
+
+    fn from_str(text: &str) -> Result<Self, ParseError> {
+        let mut parser = Parser {
+            tokens: tokenize(text)?,
+            next: 0,
+        };
+
+        let wrapped = matches!(
+            (parser.peek(), parser.tokens.get(1).map(|(_, token)| token)),
+            (Some(Token::Ident(name)), Some(Token::Open)) if name == "O"
+        );
+        if wrapped {
+            parser.next = 2;
+        }
+        let bound = parser.sum()?;
+        if wrapped {
+            parser.expect(Token::Close)?;
+        }
+        if let Some((position, token)) = parser.tokens.get(parser.next) {
+            return Err(ParseError::Unexpected {
+                position: *position,
+                found: token.to_string(),
+            });
+        }
+
+        Ok(Complexity::from(bound))
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Token {
+    Ident(String),
+    Number(u64),
+    Plus,
+    Times,
+    Caret,
+    Slash,
+    Open,
+    Close,
+}
+
+impl fmt::Display for Token {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Token::Ident(name) => f.write_str(name),
+            Token::Number(value) => write!(f, "{value}"),
+            Token::Plus => f.write_str("+"),
+            Token::Times => f.write_str("*"),
+            Token::Caret => f.write_str("^"),
+            Token::Slash => f.write_str("/"),
+            Token::Open => f.write_str("("),
+            Token::Close => f.write_str(")"),
+        }
+    }
+}
+
+/// Words the grammar gives a meaning of its own
+const RESERVED: [&str; 3] = ["log", "sqrt", "O"];
+
+/// Accept `name` only if `tokenize` reads it as one identifier and the parser
+/// as a variable
+pub(crate) fn check_variable(name: &str) -> Result<(), ParseError> {
+    let mut chars = name.chars().enumerate();
+    match chars.next() {
+        None => return Err(ParseError::UnexpectedEnd),
+        Some((_, c)) if c.is_alphabetic() => {}
+        Some((position, c)) => {
+            return Err(ParseError::Unexpected {
+                position,
+                found: c.to_string(),
+            })
+        }
+    }
+    if let Some((position, c)) = chars.find(|(_, c)| !c.is_alphanumeric() && *c != '_') {
+        return Err(ParseError::Unexpected {
+            position,
+            found: c.to_string(),
+        });
+    }
+    if RESERVED.contains(&name) {
+        return Err(ParseError::Unexpected {
+            position: 0,
+            found: name.to_string(),
+        });
+    }
+    Ok(())
+}
+
+/// Split into tokens tagged with their character position
+fn tokenize(text: &str) -> Result<Vec<(usize, Token)>, ParseError> {
+    let chars: Vec<char> = text.chars().collect();
+    let mut tokens = Vec::new();
+    let mut position = 0;
+
+    while let Some(&c) = chars.get(position) {
+        let start = position;
+        position += 1;
+        let token = match c {
+            c if c.is_whitespace() => continue,
+            '+' => Token::Plus,
+            '*' | '·' | '×' => Token::Times,
+            '^' => Token::Caret,
+            '/' => Token::Slash,
+            '(' => Token::Open,
+            ')' => Token::Close,
+            '√' => Token::Ident("sqrt".to_string()),
+            c if c.is_ascii_digit() => {
+                while chars.get(position).is_some_and(char::is_ascii_digit) {
+                    position += 1;
+                }
+                let digits: String = chars[start..position].iter().collect();
+                Token::Number(
+                    digits
+                        .parse()
+                        .map_err(|_| ParseError::Overflow { position: start })?,
+                )
+            }
+            c if c.is_alphabetic() => {
+                while chars
+                    .get(position)
+                    .is_some_and(|c| c.is_alphanumeric() || *c == '_')
+                {
+                    position += 1;
+                }
+                Token::Ident(chars[start..position].iter().collect())
+            }
+            other => {
+                return Err(ParseError::Unexpected {
+                    position: start,
+                    found: other.to_string(),
+                })
+            }
+        };
+        tokens.push((start, token));
+    }
+
+    Ok(tokens)
+}
+
+/// Recursive-descent parser over the token list
+struct Parser {
+    tokens: Vec<(usize, Token)>,
+    next: usize,
+}
+
+impl Parser {
+    /// Character position of the next token, or of the end of input
+    fn position(&self) -> usize {
+        self.tokens
+            .get(self.next)
+            .or(self.tokens.last())
+            .map_or(0, |(position, _)| *position)
+    }
+
+    fn peek(&self) -> Option<&Token> {
+        self.tokens.get(self.next).map(|(_, token)| token)
+    }
+
+    fn advance(&mut self) -> Result<(usize, Token), ParseError> {
+        let token = self
+            .tokens
+            .get(self.next)
+            .cloned()
+            .ok_or(ParseError::UnexpectedEnd)?;
+        self.next += 1;
+        Ok(token)
+    }
+
+    fn expect(&mut self, expected: Token) -> Result<(), ParseError> {
+        match self.advance()? {
+            (_, token) if token == expected => Ok(()),
+            (position, token) => Err(ParseError::Unexpected {
+                position,
+                found: token.to_string(),
+            }),
+        }
+    }
+
+    /// `sum := product ('+' product)*`
+    fn sum(&mut self) -> Result<Bound, ParseError> {
+        let mut bound = self.product()?;
+        while self.peek() == Some(&Token::Plus) {
+            self.next += 1;
+            bound = bound.add(&self.product()?);
+        }
+        Ok(bound)
+    }
+
+    /// `product := power (('*')? power)*`, juxtaposition meaning `*`
+    fn product(&mut self) -> Result<Bound, ParseError> {
+        let mut bound = self.power()?;
+        loop {
+            match self.peek() {
+                Some(Token::Times) => self.next += 1,
+                Some(Token::Ident(_) | Token::Number(_) | Token::Open) => {}
+                _ => return Ok(bound),
+            }
+            let position = self.position();
+            bound = bound
+                .checked_mul(&self.power()?)
+                .ok_or(ParseError::Overflow { position })?;
+        }
+    }
+
+    /// `power := atom ('^' ratio)?`
+    fn power(&mut self) -> Result<Bound, ParseError> {
+        let bound = self.atom()?;
+        if self.peek() != Some(&Token::Caret) {
+            return Ok(bound);
+        }
+
+        let position = self.tokens[self.next].0;
+        self.next += 1;
+        let ratio = self.ratio()?;
+        pow(&bound, ratio, position)
+    }
+
+    /// `atom := number | number '^' ident | 'log' ('^' number)? argument | 'sqrt' atom
+    ///        | ident | '(' sum ')'`
+    fn atom(&mut self) -> Result<Bound, ParseError> {
+        let (position, token) = self.advance()?;
+        match token {
+            // `b^x` with `b = 2^c` is 2^(c·x); any other bare number is O(1)
+            Token::Number(base) => {
+                let exponential = matches!(
+                    (
+                        self.tokens.get(self.next).map(|(_, t)| t),
+                        self.tokens.get(self.next + 1).map(|(_, t)| t),
+                    ),
+                    (Some(Token::Caret), Some(Token::Ident(_)))
+                );
+                if !exponential {
+                    return Ok(Bound::constant());
+                }
+                if !base.is_power_of_two() || base == 1 {
+                    return Err(ParseError::Unexpected {
+                        position,
+                        found: base.to_string(),
+                    });
+                }
+                self.next += 1;
+                let name = self.ident()?;
+                Ok(Bound::single(
+                    &name,
+                    Growth {
+                        exponential: base.trailing_zeros(),
+                        ..Growth::default()
+                    },
+                ))
+            }
+            Token::Ident(name) if name == "log" => {
+                let mut log = 1;
+                if self.peek() == Some(&Token::Caret) {
+                    self.next += 1;
+                    log = self.number()?;
+                }
+                let grouped = self.peek() == Some(&Token::Open);
+                if grouped {
+                    self.next += 1;
+                }
+                let name = self.ident()?;
+                // log(x^k) = k log x: the same class, unless k = 0 makes it log 1
+                if self.peek() == Some(&Token::Caret) {
+                    self.next += 1;
+                    if self.ratio()? == Ratio::ZERO {
+                        log = 0;
+                    }
+                }
+                if grouped {
+                    self.expect(Token::Close)?;
+                }
+                Ok(Bound::single(
+                    &name,
+                    Growth {
+                        log,
+                        ..Growth::default()
+                    },
+                ))
+            }
+            Token::Ident(name) if name == "sqrt" => pow(&self.atom()?, Ratio::new(1, 2), position),
+            Token::Ident(name) => Ok(Bound::single(
+                &name,
+                Growth {
+                    power: Ratio::ONE,
+                    ..Growth::default()
+                },
+            )),
+            Token::Open => {
+                let bound = self.sum()?;
+                self.expect(Token::Close)?;
+                Ok(bound)
+            }
+            other => Err(ParseError::Unexpected {
+                position,
+                found: other.to_string(),
+            }),
+        }
+    }
+
+    /// `ratio := number ('/' number)? | '(' number ('/' number)? ')'`
+    fn ratio(&mut self) -> Result<Ratio, ParseError> {
+        let grouped = self.peek() == Some(&Token::Open);
+        if grouped {
+            self.next += 1;
+        }
+
+        let num = self.number()?;
+        let mut den = 1;
+        if self.peek() == Some(&Token::Slash) {
+            self.next += 1;
+            let position = self
+                .tokens
+                .get(self.next)
+                .map_or(0, |(position, _)| *position);
+            den = self.number()?;
+            if den == 0 {
+                return Err(ParseError::Unexpected {
+                    position,
+                    found: "0".to_string(),
+                });
+            }
+        }
+
+        if grouped {
+            self.expect(Token::Close)?;
+        }
+        Ok(Ratio::new(num, den))
+    }
+
+    fn number(&mut self) -> Result<u32, ParseError> {
+        match self.advance()? {
+            (position, Token::Number(value)) => {
+                u32::try_from(value).map_err(|_| ParseError::Overflow { position })
+            }
+            (position, token) => Err(ParseError::Unexpected {
+                position,
+                found: token.to_string(),
+            }),
+        }
+    }
+
+    fn ident(&mut self) -> Result<String, ParseError> {
+        match self.advance()? {
+            (_, Token::Ident(name)) if name != "log" && name != "sqrt" => Ok(name),
+            (position, token) => Err(ParseError::Unexpected {
+                position,
+                found: token.to_string(),
+            }),
+        }
+    }
+}
+
+/// `bound^ratio`, with the error placed at the power's `position`
+fn pow(bound: &Bound, ratio: Ratio, position: usize) -> Result<Bound, ParseError> {
+    bound.pow(ratio).map_err(|error| match error {
+        PowError::Fractional => ParseError::FractionalExponent { position },
+        PowError::Overflow => ParseError::Overflow { position },
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(text: &str) -> Complexity {
+        text.parse().unwrap()
+    }
+
+    #[test]
+    fn named_classes_parse() {
+        assert_eq!(parse("O(1)"), Complexity::Constant);
+        assert_eq!(parse("log n"), Complexity::LogN);
+        assert_eq!(parse("log^3 n"), Complexity::PolyLogN(3));
+        assert_eq!(parse("√n"), Complexity::SqrtN);
+        assert_eq!(parse("n * log(n)"), Complexity::NLogN);
+        assert_eq!(parse("n^2 + n log n"), Complexity::Polynomial(2));
+        assert_eq!(parse("O(2^n + n^3)"), Complexity::Exponential);
+    }
+
+    #[test]
+    fn log_binds_its_arguments_power() {
+        assert_eq!(parse("log n^2"), Complexity::LogN);
+        assert_eq!(parse("log(n^3/2)"), Complexity::LogN);
+        assert_eq!(parse("log n^0"), Complexity::Constant);
+        assert_eq!(parse("(log n)^2"), Complexity::PolyLogN(2));
+        assert_eq!(parse("log^100000 n ^ 100000"), Complexity::PolyLogN(100000));
+    }
+
+    #[test]
+    fn display_round_trips() {
+        let samples = [
+            "1",
+            "n log n",
+            "log^7 n",
+            "n^3/2",
+            "V + E",
+            "(V + E) log V",
+            "V^2 + E log^2 V",
+            "2^n",
+            "4^n n",
+            "sqrt E",
+        ];
+        for text in samples {
+            let complexity = parse(text);
+            assert_eq!(parse(&complexity.to_string()), complexity, "{text}");
+        }
+
+        // 2^(c n) for every width the printer uses
+        for c in [2, 31, 32, 40, 63, 64, 70] {
+            let complexity = parse(&vec!["2^n"; c].join(" "));
+            assert_eq!(parse(&complexity.to_string()), complexity, "2^({c} n)");
+        }
+    }
+
+    #[test]
+    fn oversized_exponents_are_errors() {
+        assert_eq!(
+            "log^4000000000 n * log^4000000000 n".parse::<Complexity>(),
+            Err(ParseError::Overflow { position: 19 })
+        );
+        assert_eq!(
+            "(log^4000000000 n)^2".parse::<Complexity>(),
+            Err(ParseError::Overflow { position: 18 })
+        );
+        assert_eq!(
+            "n^99999999999".parse::<Complexity>(),
+            Err(ParseError::Overflow { position: 2 })
+        );
+        assert_eq!(
+            "99999999999999999999999^n".parse::<Complexity>(),
+            Err(ParseError::Overflow { position: 0 })
+        );
+        assert_eq!(
+            "n^4000000000 n^4000000000".parse::<Complexity>(),
+            Err(ParseError::Overflow { position: 13 })
+        );
+    }
+
+    #[test]
+    fn malformed_input_is_located() {
+        assert_eq!("".parse::<Complexity>(), Err(ParseError::UnexpectedEnd));
+        assert_eq!("O(n".parse::<Complexity>(), Err(ParseError::UnexpectedEnd));
+        assert_eq!(
+            "3^n".parse::<Complexity>(),
+            Err(ParseError::Unexpected {
+                position: 0,
+                found: "3".to_string()
+            })
+        );
+        assert_eq!(
+            "(2^n)^1/2".parse::<Complexity>(),
+            Err(ParseError::FractionalExponent { position: 5 })
+        );
+        assert_eq!(
+            "n $".parse::<Complexity>(),
+            Err(ParseError::Unexpected {
+                position: 2,
+                found: "$".to_string()
+            })
+        );
+    }
+}
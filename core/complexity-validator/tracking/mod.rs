@@ -0,0 +1,275 @@
+// core/complexity-validator/tracking/mod.rs
+
+use std::alloc::{GlobalAlloc, Layout, System};
+use std::cell::Cell;
+use std::marker::PhantomData;
+use std::sync::atomic::{AtomicBool, Ordering};
+
+use dag_engine::AuxSpaceViolation;
+
+use super::AllocationProbe;
+
+thread_local! {
+    // Live bytes on this thread; negative after freeing memory allocated elsewhere
+    static CURRENT: Cell<isize> = const { Cell::new(0) };
+    // Highest `CURRENT` since the innermost open window started
+    static PEAK: Cell<isize> = const { Cell::new(0) };
+    // `CURRENT` when the probe was last reset
+    static PROBE_BASE: Cell<isize> = const { Cell::new(0) };
+}
+
+static INSTALLED: AtomicBool = AtomicBool::new(false);
+
+/// Allocator wrapper counting live and peak bytes per thread
+///
+/// Opt-in: install it as the global allocator of a test or binary.
+///
+/// ```ignore
+/// #[global_allocator]
+/// static ALLOC: CountingAlloc = CountingAlloc::system();
+/// ```
+///
+/// Without it, every guard and probe measures zero.
+#[derive(Debug, Default)]
+pub struct CountingAlloc<A = System> {
+    inner: A,
+}
+
+impl CountingAlloc<System> {
+    /// Counting wrapper around the system allocator
+    pub const fn system() -> Self {
+        CountingAlloc { inner: System }
+    }
+}
+
+impl<A> CountingAlloc<A> {
+    /// Counting wrapper around `inner`
+    pub const fn new(inner: A) -> Self {
+        CountingAlloc { inner }
+    }
+}
+
+/// True once a `CountingAlloc` has served an allocation
+pub fn installed() -> bool {
+    INSTALLED.load(Ordering::Relaxed)
+}
+
+/// Add `delta` live bytes on this thread, raising the peak
+fn record(delta: isize) {
+    // Load first: a store on every allocation would bounce the cache line between threads
+    if !INSTALLED.load(Ordering::Relaxed) {
+        INSTALLED.store(true, Ordering::Relaxed);
+    }
+    let current = CURRENT.with(|current| {
+        current.set(current.get() + delta);
+        current.get()
+    });
+    PEAK.with(|peak| peak.set(peak.get().max(current)));
+}
+
+unsafe impl<A: GlobalAlloc> GlobalAlloc for CountingAlloc<A> {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        let ptr = self.inner.alloc(layout);
+        if !ptr.is_null() {
+            record(layout.size() as isize);
+        }
+        ptr
+    }
+
+    unsafe fn alloc_zeroed(&self, layout: Layout) -> *mut u8 {
+        let ptr = self.inner.alloc_zeroed(layout);
+        if !ptr.is_null() {
+            record(layout.size() as isize);
+        }
+        ptr
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        self.inner.dealloc(ptr, layout);
+        record(-(layout.size() as isize));
+    }
+
+    unsafe fn realloc(&self, ptr: *mut u8, layout: Layout, new_size: usize) -> *mut u8 {
+        let new = self.inner.realloc(ptr, layout, new_size);
+        if !new.is_null() {
+            record(new_size as isize - layout.size() as isize);
+        }
+        new
+    }
+}
+
+/// Peak bytes on this thread since the last `reset`
+///
+/// The reset starts a fresh peak, so do not probe inside an open guard.
+impl<A> AllocationProbe for CountingAlloc<A> {
+    fn reset(&self) {
+        let current = CURRENT.with(Cell::get);
+        PROBE_BASE.with(|base| base.set(current));
+        PEAK.with(|peak| peak.set(current));
+    }
+
+    fn peak(&self) -> usize {
+        let base = PROBE_BASE.with(Cell::get);
+        PEAK.with(|peak| (peak.get() - base).max(0) as usize)
+    }
+}
+
+/// `bytes_per_level · (⌊log2 n⌋ + 1)`, saturating: O(log n) auxiliary space
+pub fn log_budget(bytes_per_level: usize) -> impl Fn(usize) -> usize + Copy {
+    move |n| bytes_per_level.saturating_mul((usize::BITS - n.leading_zeros()).max(1) as usize)
+}
+
+/// Scoped window over this thread's allocations, checked against a budget
+///
+/// Measures the peak of bytes allocated and still live while the guard is
+/// open. Guards nest: closing an inner one folds its peak into the outer.
+/// The counters are per thread, so a guard cannot be sent to another one.
+#[derive(Debug)]
+pub struct AuxSpaceGuard {
+    scope: &'static str,
+    node_count: usize,
+    allowed: usize,
+    base: isize,
+    outer_peak: isize,
+    _not_send: PhantomData<*const ()>,
+}
+
+impl AuxSpaceGuard {
+    /// Open a window for `scope` allowed `budget(node_count)` bytes
+    pub fn new<B>(scope: &'static str, node_count: usize, budget: B) -> Self
+    where
+        B: Fn(usize) -> usize,
+    {
+        let base = CURRENT.with(Cell::get);
+        let outer_peak = PEAK.with(|peak| peak.replace(base));
+        AuxSpaceGuard {
+            scope,
+            node_count,
+            allowed: budget(node_count),
+            base,
+            outer_peak,
+            _not_send: PhantomData,
+        }
+    }
+
+    /// Run `f` inside a guard, failing if it allocated past the budget
+    pub fn scope<B, F, R>(
+        scope: &'static str,
+        node_count: usize,
+        budget: B,
+        f: F,
+    ) -> Result<R, AuxSpaceViolation>
+    where
+        B: Fn(usize) -> usize,
+        F: FnOnce() -> R,
+    {
+        let guard = Self::new(scope, node_count, budget);
+        let result = f();
+        guard.finish()?;
+        Ok(result)
+    }
+
+    /// Peak bytes so far in this window
+    pub fn measured(&self) -> usize {
+        PEAK.with(|peak| (peak.get() - self.base).max(0) as usize)
+    }
+
+    /// Bytes this window may reach
+    pub fn allowed(&self) -> usize {
+        self.allowed
+    }
+
+    /// Close the window, returning the peak or the budget violation
+    pub fn finish(self) -> Result<usize, AuxSpaceViolation> {
+        let measured = self.measured();
+        if measured > self.allowed {
+            return Err(AuxSpaceViolation::BudgetExceeded {
+                scope: self.scope,
+                measured,
+                allowed: self.allowed,
+                node_count: self.node_count,
+            });
+        }
+        Ok(measured)
+    }
+}
+
+impl Drop for AuxSpaceGuard {
+    fn drop(&mut self) {
+        PEAK.with(|peak| peak.set(peak.get().max(self.outer_peak)));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[global_allocator]
+    static ALLOC: CountingAlloc = CountingAlloc::system();
+
+    #[test]
+    fn counting_allocator_is_installed() {
+        drop(Vec::<u8>::with_capacity(1));
+        assert!(installed());
+    }
+
+    #[test]
+    fn guard_measures_the_peak_of_live_bytes() {
+        let guard = AuxSpaceGuard::new("test", 0, |_| 4096);
+        let block = Vec::<u8>::with_capacity(1000);
+        drop(block);
+        let again = Vec::<u8>::with_capacity(600);
+        assert_eq!(guard.measured(), 1000);
+        drop(again);
+        assert_eq!(guard.finish(), Ok(1000));
+    }
+
+    #[test]
+    fn scope_reports_budget_overruns() {
+        let result = AuxSpaceGuard::scope("scan", 16, log_budget(64), || {
+            Vec::<u8>::with_capacity(10_000).capacity()
+        });
+        assert_eq!(
+            result,
+            Err(AuxSpaceViolation::BudgetExceeded {
+                scope: "scan",
+                measured: 10_000,
+                allowed: 64 * 5,
+                node_count: 16,
+            })
+        );
+
+        let within = AuxSpaceGuard::scope("lookup", 16, log_budget(64), || 1 + 1);
+        assert_eq!(within, Ok(2));
+    }
+
+    #[test]
+    fn inner_guards_fold_into_the_outer_peak() {
+        let outer = AuxSpaceGuard::new("outer", 0, |_| usize::MAX);
+        let held = Vec::<u8>::with_capacity(100);
+        {
+            let inner = AuxSpaceGuard::new("inner", 0, |_| usize::MAX);
+            drop(Vec::<u8>::with_capacity(500));
+            assert_eq!(inner.finish(), Ok(500));
+        }
+        assert_eq!(outer.measured(), 600);
+        drop(held);
+    }
+
+    #[test]
+    fn probe_measures_since_reset() {
+        let _warm = Vec::<u8>::with_capacity(2000);
+        ALLOC.reset();
+        drop(Vec::<u8>::with_capacity(300));
+        assert_eq!(ALLOC.peak(), 300);
+    }
+
+    #[test]
+    fn log_budget_grows_with_bit_length() {
+        let budget = log_budget(10);
+        assert_eq!(budget(0), 10);
+        assert_eq!(budget(1), 10);
+        assert_eq!(budget(1024), 110);
+        assert_eq!(log_budget(usize::MAX)(usize::MAX), usize::MAX);
+    }
+}
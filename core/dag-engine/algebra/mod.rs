@@ -0,0 +1,438 @@
+// core/dag-engine/algebra/mod.rs
+
+use std::collections::{BTreeMap, BTreeSet};
+
+use super::{
+    Adjacency, DepthViolation, EdgeError, IsomorphicDag, LosslessDag, Node, NodeId, Slot, Stamped,
+    Tick, DAG,
+};
+
+/// Union (∪): merge two DAGs, identifying nodes with equal `key`
+///
+/// Nodes sharing a key — across or within operands — are combined with
+/// `merge(earlier, later)`, left operand first. The edge set is the union of
+/// both edge sets. New ids follow key order; the left operand's policy is kept.
+/// Fails if the union closes a cycle or breaks the depth bound.
+///
+/// Laws, on key sets and keyed edge sets:
+/// - `union(a, b) = union(b, a)`
+/// - `union(union(a, b), c) = union(a, union(b, c))`
+/// - `union(a, a) = a`
+pub fn union<T, K, F, M>(
+    left: DAG<T>,
+    right: DAG<T>,
+    mut key: F,
+    mut merge: M,
+) -> Result<DAG<T>, EdgeError>
+where
+    K: Ord + Clone,
+    F: FnMut(&T) -> K,
+    M: FnMut(T, T) -> T,
+{
+    let mut merged: BTreeMap<K, T> = BTreeMap::new();
+    let mut edges: BTreeSet<(K, K)> = BTreeSet::new();
+    let mut result = DAG {
+        policy: left.policy.clone(),
+        ..DAG::new()
+    };
+
+    for dag in [left, right] {
+        let keys: Vec<Option<K>> = dag
+            .slots
+            .iter()
+            .map(|slot| slot.node.as_ref().map(|node| key(&node.data)))
+            .collect();
+
+        for (index, slot) in dag.slots.into_iter().enumerate() {
+            let Some(node) = slot.node else {
+                continue;
+            };
+            let from = keys[index].clone().expect("live slot has a key");
+            for child in node.edges {
+                let to = keys[child.index()].clone().expect("edge target is a node");
+                edges.insert((from.clone(), to));
+            }
+
+            let data = match merged.remove(&from) {
+                Some(earlier) => merge(earlier, node.data),
+                None => node.data,
+            };
+            merged.insert(from, data);
+        }
+    }
+
+    let mut ids: BTreeMap<K, NodeId> = BTreeMap::new();
+    for (key, data) in merged {
+        ids.insert(key, result.add_node(data)?);
+    }
+    for (from, to) in edges {
+        result.add_edge(ids[&from], ids[&to])?;
+    }
+
+    Ok(result)
+}
+
+/// Lossless union (∪ with Time ⊗): keyed union that keeps every state
+///
+/// Nodes are keyed by their oldest state; merged histories stay in tick order.
+pub fn union_lossless<T, K, F>(
+    left: LosslessDag<T>,
+    right: LosslessDag<T>,
+    mut key: F,
+) -> Result<LosslessDag<T>, EdgeError>
+where
+    K: Ord + Clone,
+    F: FnMut(&T) -> K,
+{
+    let dag = union(
+        left.into_dag(),
+        right.into_dag(),
+        |history| key(&history[0].state),
+        |mut earlier, later| {
+            earlier.extend(later);
+            earlier.sort_by_key(|stamped| stamped.at);
+            earlier
+        },
+    )?;
+
+    Ok(LosslessDag::try_from_dag(dag).expect("merged histories stay non-empty and tick-ordered"))
+}
+
+/// Disjoint sum (⊔): place two DAGs side by side as partitions 0 and 1
+///
+/// Left ids are unchanged; the returned map gives the new id of every right
+/// node. The left operand's policy is kept and re-checked on the combined graph.
+///
+/// Laws:
+/// - `|a ⊔ b| = |a| + |b|`, and no edge joins the two partitions
+/// - the subgraph on partition 0 is `a`, on partition 1 is `b` (up to ids)
+pub fn disjoint_sum<T>(
+    left: DAG<T>,
+    right: DAG<T>,
+) -> Result<(IsomorphicDag<T>, BTreeMap<NodeId, NodeId>), DepthViolation> {
+    let offset = u32::try_from(left.slots.len()).expect("DAG exceeds u32::MAX slots");
+    let shift = |id: NodeId| NodeId {
+        index: id.index + offset,
+        generation: id.generation,
+    };
+
+    let renamed: BTreeMap<NodeId, NodeId> = right.ids().map(|id| (id, shift(id))).collect();
+
+    let mut slots = left.slots;
+    slots.extend(right.slots.into_iter().map(|slot| Slot {
+        generation: slot.generation,
+        node: slot.node.map(|mut node| {
+            node.edges
+                .iter_mut()
+                .for_each(|child| *child = shift(*child));
+            node
+        }),
+    }));
+
+    let mut sum = DAG::from_slots(slots, left.policy);
+    let depth = sum.max_depth;
+    sum.check_depth(depth, DAG::deepest_chain)?;
+
+    let sum = IsomorphicDag::try_from_dag(sum, |id, _| u32::from(id.index >= offset))
+        .expect("operands share no edges");
+    Ok((sum, renamed))
+}
+
+/// Pairing (⊗): product of two DAGs, every node pair stamped by `clock`
+///
+/// There is one node `(e, f)` for each node `e` of `left` and `f` of `right`,
+/// numbered left-major, with edges `(e, f) → (e', f)` for each `e → e'` and
+/// `(e, f) → (e, f')` for each `f → f'`. Its history is the single state
+/// `(e, f)` stamped at `clock(e, f)`. The left operand's policy is kept and
+/// re-checked on the product.
+///
+/// Laws:
+/// - `|a ⊗ b| = |a| · |b|`
+/// - for each node `f` of `b`, the nodes `(·, f)` span a copy of `a`, and for
+///   each node `e` of `a`, the nodes `(e, ·)` span a copy of `b`
+pub fn pair<T, U, C>(
+    left: DAG<T>,
+    right: DAG<U>,
+    mut clock: C,
+) -> Result<LosslessDag<(T, U)>, DepthViolation>
+where
+    T: Clone,
+    U: Clone,
+    C: FnMut(NodeId, NodeId) -> Tick,
+{
+    let (left_ids, right_ids): (Vec<NodeId>, Vec<NodeId>) =
+        (left.ids().collect(), right.ids().collect());
+    let position =
+        |ids: &[NodeId], id: NodeId| ids.binary_search(&id).expect("edge target is a node");
+    let width = right_ids.len();
+    let id_of = |e: usize, f: usize| NodeId {
+        index: u32::try_from(e * width + f).expect("product exceeds u32::MAX nodes"),
+        generation: 0,
+    };
+
+    let mut slots = Vec::with_capacity(left_ids.len() * width);
+    for (e, &left_id) in left_ids.iter().enumerate() {
+        for (f, &right_id) in right_ids.iter().enumerate() {
+            let along_left = left
+                .children(left_id)
+                .iter()
+                .map(|&child| id_of(position(&left_ids, child), f));
+            let along_right = right
+                .children(right_id)
+                .iter()
+                .map(|&child| id_of(e, position(&right_ids, child)));
+            slots.push(Slot {
+                generation: 0,
+                node: Some(Node {
+                    data: vec![Stamped {
+                        at: clock(left_id, right_id),
+                        state: (left[left_id].clone(), right[right_id].clone()),
+                    }],
+                    edges: along_left.chain(along_right).collect(),
+                }),
+            });
+        }
+    }
+
+    let mut product = DAG::from_slots(slots, left.policy);
+    let depth = product.max_depth;
+    product.check_depth(depth, DAG::deepest_chain)?;
+
+    Ok(LosslessDag::try_from_dag(product).expect("single-state histories are lossless"))
+}
+
+/// Division (⊘): split a DAG into its weakly connected components
+///
+/// Components keep their original ids and are ordered by smallest id;
+/// [`DAG::rejoin`] is the inverse.
+///
+/// Laws:
+/// - components are pairwise disjoint and cover every node
+/// - every edge lies inside exactly one component
+pub fn divide<T>(dag: DAG<T>) -> Vec<DAG<T>> {
+    dag.into_components()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::FixedCap;
+
+    type Keyed = (BTreeSet<char>, BTreeSet<(char, char)>);
+
+    /// Cases generated per law
+    const CASES: u64 = 64;
+
+    /// Deterministic DAGs over keys `a..=h`; edges only run from a smaller to a
+    /// larger key, so unions of generated DAGs are always acyclic
+    struct Gen(u64);
+
+    impl Gen {
+        fn next(&mut self) -> u64 {
+            // SplitMix64
+            self.0 = self.0.wrapping_add(0x9E37_79B9_7F4A_7C15);
+            let mut z = self.0;
+            z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+            z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+            z ^ (z >> 31)
+        }
+
+        fn dag(&mut self) -> DAG<char> {
+            let keys: Vec<char> = ('a'..='h')
+                .filter(|_| self.next().is_multiple_of(2))
+                .collect();
+            let mut edges = Vec::new();
+            for (i, &from) in keys.iter().enumerate() {
+                for &to in &keys[i + 1..] {
+                    if self.next().is_multiple_of(3) {
+                        edges.push((from, to));
+                    }
+                }
+            }
+            build(&keys, &edges)
+        }
+    }
+
+    /// DAG over `keys` with the given edges between keys
+    fn build(keys: &[char], edges: &[(char, char)]) -> DAG<char> {
+        let mut dag = DAG::with_policy(FixedCap(8));
+        let ids: BTreeMap<char, NodeId> = keys
+            .iter()
+            .map(|&c| (c, dag.add_node(c).unwrap()))
+            .collect();
+        for (from, to) in edges {
+            dag.add_edge(ids[from], ids[to]).unwrap();
+        }
+        dag
+    }
+
+    /// Key set and keyed edge set
+    fn keyed(dag: &DAG<char>) -> Keyed {
+        let keys = dag.ids().map(|id| dag[id]).collect();
+        let edges = dag
+            .ids()
+            .flat_map(|id| dag.children(id).iter().map(move |&c| (dag[id], dag[c])))
+            .collect();
+        (keys, edges)
+    }
+
+    fn join(a: DAG<char>, b: DAG<char>) -> DAG<char> {
+        union(a, b, |&c| c, |earlier, _| earlier).unwrap()
+    }
+
+    /// Run `law` on `CASES` generator states, each replayable from its seed
+    fn check<F: FnMut(&mut Gen)>(mut law: F) {
+        for seed in 0..CASES {
+            law(&mut Gen(seed));
+        }
+    }
+
+    #[test]
+    fn union_is_commutative() {
+        check(|g| {
+            let seed = g.0;
+            let (a, b) = (g.dag(), g.dag());
+            let mut again = Gen(seed);
+            let (a2, b2) = (again.dag(), again.dag());
+            assert_eq!(keyed(&join(a, b)), keyed(&join(b2, a2)), "seed {seed}");
+        });
+    }
+
+    #[test]
+    fn union_is_associative() {
+        check(|g| {
+            let seed = g.0;
+            let (a, b, c) = (g.dag(), g.dag(), g.dag());
+            let mut again = Gen(seed);
+            let (a2, b2, c2) = (again.dag(), again.dag(), again.dag());
+            assert_eq!(
+                keyed(&join(join(a, b), c)),
+                keyed(&join(a2, join(b2, c2))),
+                "seed {seed}"
+            );
+        });
+    }
+
+    #[test]
+    fn union_is_idempotent() {
+        check(|g| {
+            let seed = g.0;
+            let a = g.dag();
+            let a2 = Gen(seed).dag();
+            let expected = keyed(&a);
+            assert_eq!(keyed(&join(a, a2)), expected, "seed {seed}");
+        });
+    }
+
+    #[test]
+    fn union_rejects_cycles_across_operands() {
+        let a = build(&['a', 'b'], &[('a', 'b')]);
+        let b = build(&['a', 'b'], &[('b', 'a')]);
+        assert!(matches!(
+            union(a, b, |&c| c, |earlier, _| earlier),
+            Err(EdgeError::Cycle { .. })
+        ));
+    }
+
+    #[test]
+    fn disjoint_sum_adds_sizes_without_cross_edges() {
+        check(|g| {
+            let (a, b) = (g.dag(), g.dag());
+            let (len_a, len_b) = (a.len(), b.len());
+            let (keyed_a, keyed_b) = (keyed(&a), keyed(&b));
+            let left_ids: Vec<NodeId> = a.ids().collect();
+
+            let (sum, renamed) = disjoint_sum(a, b).unwrap();
+            let partitions = sum.partitions();
+            let side = |partition| {
+                let ids = partitions.get(&partition).cloned().unwrap_or_default();
+                keyed(&sum.dag().subgraph(ids).map(|part| part.data))
+            };
+
+            assert_eq!(sum.dag().len(), len_a + len_b);
+            assert_eq!(partitions.get(&0).cloned().unwrap_or_default(), left_ids);
+            assert_eq!(renamed.len(), len_b);
+            assert_eq!((side(0), side(1)), (keyed_a, keyed_b));
+            for id in sum.dag().ids() {
+                for &child in sum.dag().children(id) {
+                    assert_eq!(sum.partition(id), sum.partition(child));
+                }
+            }
+        });
+    }
+
+    #[test]
+    fn pair_multiplies_sizes_and_spans_each_operand() {
+        check(|g| {
+            let (a, b) = (g.dag(), g.dag());
+            let (keyed_a, keyed_b) = (keyed(&a), keyed(&b));
+            let (len_a, len_b) = (a.len(), b.len());
+
+            let paired = pair(a, b, |e, f| u64::from(e.index ^ f.index)).unwrap();
+            let product = paired.dag();
+            assert_eq!(product.len(), len_a * len_b);
+
+            let fiber = |keep: &dyn Fn(&(char, char)) -> bool, side: fn(&(char, char)) -> char| {
+                let ids = product.ids().filter(|&id| keep(&product[id][0].state));
+                keyed(&product.subgraph(ids).map(|part| side(&part[0].state)))
+            };
+            for &f in &keyed_b.0 {
+                assert_eq!(fiber(&|&(_, y)| y == f, |&(x, _)| x), keyed_a);
+            }
+            for &e in &keyed_a.0 {
+                assert_eq!(fiber(&|&(x, _)| x == e, |&(_, y)| y), keyed_b);
+            }
+        });
+    }
+
+    #[test]
+    fn union_lossless_merges_histories_in_tick_order() {
+        let mut a = LosslessDag::with_policy(FixedCap(8));
+        let (x, y) = (a.add_node('x', 1).unwrap(), a.add_node('y', 4).unwrap());
+        a.merge(x, 'X', 5);
+        a.add_edge(x, y).unwrap();
+
+        let mut b = LosslessDag::with_policy(FixedCap(8));
+        let (x2, z) = (b.add_node('x', 3).unwrap(), b.add_node('z', 2).unwrap());
+        b.add_edge(x2, z).unwrap();
+
+        let merged = union_lossless(a, b, |&c| c).unwrap();
+        let by_key: BTreeMap<char, NodeId> = merged
+            .dag()
+            .ids()
+            .map(|id| (merged.history(id)[0].state, id))
+            .collect();
+        let ticks = |key| {
+            merged
+                .history(by_key[&key])
+                .iter()
+                .map(|stamped| (stamped.at, stamped.state))
+                .collect::<Vec<_>>()
+        };
+
+        assert_eq!(by_key.len(), 3);
+        assert_eq!(ticks('x'), vec![(1, 'x'), (3, 'x'), (5, 'X')]);
+        assert_eq!(ticks('z'), vec![(2, 'z')]);
+        let mut children = merged.dag().children(by_key[&'x']).to_vec();
+        children.sort();
+        assert_eq!(children, vec![by_key[&'y'], by_key[&'z']]);
+    }
+
+    #[test]
+    fn rejoin_inverts_divide() {
+        check(|g| {
+            let dag = g.dag();
+            let expected = keyed(&dag);
+            let ids: Vec<NodeId> = dag.ids().collect();
+
+            let parts = divide(dag);
+            let mut covered: Vec<NodeId> = parts.iter().flat_map(|part| part.ids()).collect();
+            covered.sort();
+            assert_eq!(covered, ids, "components cover every node once");
+
+            let rejoined = DAG::rejoin(parts).unwrap();
+            assert_eq!(rejoined.ids().collect::<Vec<_>>(), ids);
+            assert_eq!(keyed(&rejoined), expected);
+        });
+    }
+}
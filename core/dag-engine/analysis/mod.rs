@@ -0,0 +1,242 @@
+// core/dag-engine/analysis/mod.rs
+
+use std::collections::BTreeMap;
+
+use super::{Adjacency, NodeId, DAG};
+
+/// Depth profile of a single node
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct NodeDepth {
+    /// Longest path from any root, in edges
+    pub depth: usize,
+    /// Longest path to any leaf, in edges
+    pub height: usize,
+    /// `depth` is within the policy bound
+    pub within_bound: bool,
+}
+
+/// Structured depth analysis returned by [`DAG::depth_report`]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DepthReport {
+    pub node_count: usize,
+    /// Policy bound for `node_count` nodes
+    pub bound: usize,
+    pub max_depth: usize,
+    pub nodes: BTreeMap<NodeId, NodeDepth>,
+    /// Number of nodes at each depth
+    pub layer_widths: Vec<usize>,
+    /// Costliest root-to-leaf path, root first
+    pub critical_path: Vec<NodeId>,
+    /// Summed node cost along `critical_path`
+    pub critical_cost: u64,
+    deepest_parent: BTreeMap<NodeId, NodeId>, // Parent on the longest chain to each node
+}
+
+impl DepthReport {
+    /// Longest root-to-node chain ending at `id`, root first
+    pub fn chain_to(&self, id: NodeId) -> Vec<NodeId> {
+        if !self.nodes.contains_key(&id) {
+            return Vec::new();
+        }
+
+        let mut chain = vec![id];
+        let mut cursor = id;
+        while let Some(&parent) = self.deepest_parent.get(&cursor) {
+            chain.push(parent);
+            cursor = parent;
+        }
+        chain.reverse();
+        chain
+    }
+
+    /// Every node past the bound, with the chain that pushed it there
+    pub fn violations(&self) -> Vec<(NodeId, Vec<NodeId>)> {
+        self.nodes
+            .iter()
+            .filter(|(_, node)| !node.within_bound)
+            .map(|(&id, _)| (id, self.chain_to(id)))
+            .collect()
+    }
+}
+
+impl<T> DAG<T> {
+    /// Depth report with every node costing 1, so the critical path is the longest chain
+    pub fn depth_report(&self) -> DepthReport {
+        self.depth_report_weighted(|_, _| 1)
+    }
+
+    /// Depth report whose critical path maximises the summed `cost` of its nodes
+    ///
+    /// Ties are broken towards smaller `NodeId`s, so reports are reproducible.
+    pub fn depth_report_weighted<C>(&self, mut cost: C) -> DepthReport
+    where
+        C: FnMut(NodeId, &T) -> u64,
+    {
+        let order: Vec<NodeId> = self.topological_order().map(|(id, _)| id).collect();
+        let bound = self.depth_bound();
+
+        let (depth, deepest_parent) = self.depths();
+        let mut best = vec![0u64; self.slots.len()]; // Costliest path ending at each node
+        let mut costliest_parent: Vec<Option<NodeId>> = vec![None; self.slots.len()];
+
+        for &id in &order {
+            best[id.index()] = best[id.index()].saturating_add(cost(id, &self[id]));
+            for &child in self.children(id) {
+                let slot = child.index();
+                if prefer(best[id.index()], id, best[slot], costliest_parent[slot]) {
+                    best[slot] = best[id.index()];
+                    costliest_parent[slot] = Some(id);
+                }
+            }
+        }
+
+        let mut height = vec![0usize; self.slots.len()];
+        for &id in order.iter().rev() {
+            height[id.index()] = self
+                .children(id)
+                .iter()
+                .map(|child| height[child.index()] + 1)
+                .max()
+                .unwrap_or(0);
+        }
+
+        let mut layer_widths = Vec::new();
+        let mut nodes = BTreeMap::new();
+        for &id in &order {
+            let d = depth[id.index()];
+            if layer_widths.len() <= d {
+                layer_widths.resize(d + 1, 0);
+            }
+            layer_widths[d] += 1;
+            nodes.insert(
+                id,
+                NodeDepth {
+                    depth: d,
+                    height: height[id.index()],
+                    within_bound: d <= bound,
+                },
+            );
+        }
+
+        // Critical path ends at the costliest node, smallest id on ties
+        let mut critical_path = Vec::new();
+        let mut critical_cost = 0;
+        if let Some(end) = nodes
+            .keys()
+            .copied()
+            .max_by_key(|id| (best[id.index()], std::cmp::Reverse(*id)))
+        {
+            critical_cost = best[end.index()];
+            let mut cursor = Some(end);
+            while let Some(id) = cursor {
+                critical_path.push(id);
+                cursor = costliest_parent[id.index()];
+            }
+            critical_path.reverse();
+        }
+
+        DepthReport {
+            node_count: self.bound_len(),
+            bound,
+            max_depth: layer_widths.len().saturating_sub(1),
+            deepest_parent: nodes
+                .keys()
+                .filter_map(|&id| deepest_parent[id.index()].map(|parent| (id, parent)))
+                .collect(),
+            nodes,
+            layer_widths,
+            critical_path,
+            critical_cost,
+        }
+    }
+}
+
+/// Whether `candidate` reached via `parent` beats the current value and parent
+fn prefer<V: Ord>(
+    candidate: V,
+    parent: NodeId,
+    current: V,
+    current_parent: Option<NodeId>,
+) -> bool {
+    match current_parent {
+        None => true,
+        Some(existing) => candidate > current || (candidate == current && parent < existing),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{DepthPolicy, FixedCap, Log2, ReportOnly};
+
+    /// Diamond 0 → {1, 2} → 3, then 3 → 4
+    fn diamond<P: DepthPolicy + 'static>(policy: P) -> (DAG<u64>, Vec<NodeId>) {
+        let mut dag = DAG::with_policy(policy);
+        let ids: Vec<NodeId> = (0..5).map(|i| dag.add_node(i).unwrap()).collect();
+        for (from, to) in [(0, 1), (0, 2), (1, 3), (2, 3), (3, 4)] {
+            dag.add_edge(ids[from], ids[to]).unwrap();
+        }
+        (dag, ids)
+    }
+
+    #[test]
+    fn report_profiles_depths_and_layers() {
+        let (dag, ids) = diamond(FixedCap(8));
+        let report = dag.depth_report();
+
+        assert_eq!(report.node_count, 5);
+        assert_eq!(report.max_depth, 3);
+        assert_eq!(report.layer_widths, vec![1, 2, 1, 1]);
+        assert_eq!(report.nodes[&ids[0]].height, 3);
+        assert_eq!(report.nodes[&ids[3]].depth, 2);
+        assert!(report.violations().is_empty());
+
+        // Unit costs: the longest chain, smaller ids on ties
+        assert_eq!(report.critical_path, vec![ids[0], ids[1], ids[3], ids[4]]);
+        assert_eq!(report.critical_cost, 4);
+    }
+
+    #[test]
+    fn weighted_report_follows_the_costliest_branch() {
+        let (dag, ids) = diamond(FixedCap(8));
+        let report = dag.depth_report_weighted(|_, &value| value * 10);
+
+        assert_eq!(report.critical_path, vec![ids[0], ids[2], ids[3], ids[4]]);
+        assert_eq!(report.critical_cost, 90);
+    }
+
+    #[test]
+    fn weighted_report_saturates_huge_costs() {
+        let (dag, ids) = diamond(FixedCap(8));
+        let report = dag.depth_report_weighted(|_, _| u64::MAX / 2);
+
+        assert_eq!(report.critical_cost, u64::MAX);
+        assert_eq!(report.critical_path.first(), Some(&ids[0]));
+    }
+
+    #[test]
+    fn violations_carry_the_chain_past_the_bound() {
+        let (dag, ids) = diamond(ReportOnly(FixedCap(2)));
+        let report = dag.depth_report();
+
+        assert_eq!(report.bound, 2);
+        assert_eq!(
+            report.violations(),
+            vec![(ids[4], vec![ids[0], ids[1], ids[3], ids[4]])]
+        );
+
+        let mut trimmed = dag;
+        trimmed.remove_node(ids[4]);
+        assert!(trimmed.depth_report().chain_to(ids[4]).is_empty());
+    }
+
+    #[test]
+    fn node_count_matches_the_bound_after_removals() {
+        let (mut dag, ids) = diamond(Log2::default());
+        dag.remove_node(ids[4]);
+        let report = dag.depth_report();
+
+        assert_eq!((report.node_count, report.bound), (5, 3));
+        assert_eq!(report.bound, dag.policy.bound(report.node_count));
+    }
+}
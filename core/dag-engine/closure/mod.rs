@@ -0,0 +1,135 @@
+// core/dag-engine/closure/mod.rs
+
+use super::{BitSet, NodeId, DAG};
+
+/// Transitive closure as one bit row per node: O(1) reachability queries
+///
+/// Built in O(V · (V + E) / 64) time and O(V² / 64) space. The index is a
+/// snapshot; rebuild it after the DAG changes.
+pub struct Reachability {
+    generations: Vec<Option<u32>>, // Live generation per slot, to reject stale ids
+    rows: Vec<BitSet>,             // rows[i] = slots reachable from slot i
+}
+
+impl Reachability {
+    /// True when a non-empty path `from → … → to` exists
+    pub fn reaches(&self, from: NodeId, to: NodeId) -> bool {
+        self.is_live(from) && self.is_live(to) && self.rows[from.index()].contains(to.index())
+    }
+
+    /// Number of nodes reachable from `id`
+    pub fn descendant_count(&self, id: NodeId) -> usize {
+        if !self.is_live(id) {
+            return 0;
+        }
+        self.rows[id.index()].count()
+    }
+
+    fn is_live(&self, id: NodeId) -> bool {
+        self.generations.get(id.index()).copied().flatten() == Some(id.generation)
+    }
+}
+
+impl<T> DAG<T> {
+    /// Build the reachability index (bitset transitive closure)
+    pub fn reachability(&self) -> Reachability {
+        let mut rows = vec![BitSet::new(self.slots.len()); self.slots.len()];
+
+        // Children are complete before their parents in reverse topological order
+        let order: Vec<NodeId> = self.topological_order().map(|(id, _)| id).collect();
+        for &id in order.iter().rev() {
+            let mut row = BitSet::new(self.slots.len());
+            for child in self.children(id) {
+                row.insert(child.index());
+                row.union_with(&rows[child.index()]);
+            }
+            rows[id.index()] = row;
+        }
+
+        Reachability {
+            generations: self
+                .slots
+                .iter()
+                .map(|slot| slot.node.as_ref().map(|_| slot.generation))
+                .collect(),
+            rows,
+        }
+    }
+
+    /// Drop every edge implied by a longer path, returning how many were removed
+    ///
+    /// `u → v` is redundant when `v` is also reachable through another child of `u`.
+    /// Reachability, and therefore every node's depth, is unchanged.
+    pub fn transitive_reduction(&mut self) -> usize {
+        let reach = self.reachability();
+        let mut removed = 0;
+
+        for slot in &mut self.slots {
+            let Some(node) = slot.node.as_mut() else {
+                continue;
+            };
+            let children = node.edges.clone();
+            node.edges.retain(|&target| {
+                let implied = children.iter().any(|&other| {
+                    other != target && reach.rows[other.index()].contains(target.index())
+                });
+                removed += usize::from(implied);
+                !implied
+            });
+        }
+
+        removed
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::FixedCap;
+
+    /// 0 → 1 → 2 → 3 with shortcuts 0 → 2 and 0 → 3, plus an isolated 4
+    fn sample() -> (DAG<u8>, Vec<NodeId>) {
+        let mut dag = DAG::with_policy(FixedCap(8));
+        let ids: Vec<NodeId> = (0..5).map(|i| dag.add_node(i).unwrap()).collect();
+        for (from, to) in [(0, 1), (1, 2), (2, 3), (0, 2), (0, 3)] {
+            dag.add_edge(ids[from], ids[to]).unwrap();
+        }
+        (dag, ids)
+    }
+
+    #[test]
+    fn reachability_answers_paths() {
+        let (mut dag, ids) = sample();
+        let reach = dag.reachability();
+
+        assert!(reach.reaches(ids[1], ids[3]));
+        assert!(!reach.reaches(ids[3], ids[1]));
+        assert!(!reach.reaches(ids[0], ids[0]));
+        assert!(!reach.reaches(ids[0], ids[4]));
+        assert_eq!(reach.descendant_count(ids[0]), 3);
+        assert_eq!(reach.descendant_count(ids[4]), 0);
+
+        dag.remove_node(ids[4]);
+        let reused = dag.add_node(9).unwrap();
+        assert_eq!(dag.reachability().descendant_count(ids[4]), 0);
+        assert!(!dag.reachability().reaches(ids[0], reused));
+    }
+
+    #[test]
+    fn transitive_reduction_drops_only_implied_edges() {
+        let (mut dag, ids) = sample();
+        let before = dag.reachability();
+
+        assert_eq!(dag.transitive_reduction(), 2);
+        assert_eq!(dag.children(ids[0]), &[ids[1]]);
+        assert_eq!(dag.max_depth(), 3);
+
+        let after = dag.reachability();
+        for &from in &ids {
+            for &to in &ids {
+                assert_eq!(after.reaches(from, to), before.reaches(from, to));
+            }
+        }
+        assert_eq!(dag.transitive_reduction(), 0);
+    }
+}
@@ -0,0 +1,268 @@
+// core/dag-engine/components/mod.rs
+
+use std::collections::BTreeMap;
+use std::fmt;
+use std::sync::Arc;
+
+use super::{EdgeError, NodeId, Slot, DAG};
+
+/// Disjoint-set forest over slot indices (union by size, path halving)
+pub struct UnionFind {
+    parent: Vec<usize>,
+    size: Vec<usize>,
+}
+
+impl UnionFind {
+    /// `len` singleton sets
+    pub fn new(len: usize) -> Self {
+        Self {
+            parent: (0..len).collect(),
+            size: vec![1; len],
+        }
+    }
+
+    /// Representative of the set containing `index`
+    pub fn find(&mut self, mut index: usize) -> usize {
+        while self.parent[index] != index {
+            self.parent[index] = self.parent[self.parent[index]];
+            index = self.parent[index];
+        }
+        index
+    }
+
+    /// Merge the sets of `a` and `b`, returning whether they were distinct
+    pub fn union(&mut self, a: usize, b: usize) -> bool {
+        let (mut a, mut b) = (self.find(a), self.find(b));
+        if a == b {
+            return false;
+        }
+        if self.size[a] < self.size[b] {
+            std::mem::swap(&mut a, &mut b);
+        }
+        self.parent[b] = a;
+        self.size[a] += self.size[b];
+        true
+    }
+}
+
+/// Failure to re-merge components with [`DAG::rejoin`]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum RejoinError {
+    /// Two parts hold a live node in the same slot
+    Overlap(NodeId),
+    /// An edge points at a node no part contains
+    Edge(EdgeError),
+}
+
+impl fmt::Display for RejoinError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            RejoinError::Overlap(id) => write!(f, "more than one part holds node {id}"),
+            RejoinError::Edge(error) => write!(f, "{error}"),
+        }
+    }
+}
+
+impl std::error::Error for RejoinError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            RejoinError::Edge(error) => Some(error),
+            _ => None,
+        }
+    }
+}
+
+impl<T> DAG<T> {
+    /// Weakly connected component of every node
+    ///
+    /// Components are numbered from 0 in order of their smallest `NodeId`.
+    pub fn component_labels(&self) -> BTreeMap<NodeId, usize> {
+        let labels = self.slot_labels();
+        self.ids().map(|id| (id, labels[id.index()])).collect()
+    }
+
+    /// Copy out every weakly connected component as its own DAG, keeping ids
+    ///
+    /// Each part keeps this DAG's depth bound, so it runs on its own wherever
+    /// it ran as part of the whole.
+    pub fn components(&self) -> Vec<DAG<T>>
+    where
+        T: Clone,
+    {
+        let mut members: Vec<Vec<NodeId>> = Vec::new();
+        for (id, label) in self.component_labels() {
+            if label == members.len() {
+                members.push(Vec::new());
+            }
+            members[label].push(id);
+        }
+
+        members.into_iter().map(|ids| self.subgraph(ids)).collect()
+    }
+
+    /// Move every weakly connected component into its own DAG, keeping ids
+    ///
+    /// Each part keeps this DAG's depth bound and is independently executable;
+    /// [`DAG::rejoin`] restores the original.
+    pub fn into_components(self) -> Vec<DAG<T>> {
+        let labels = self.slot_labels();
+        let count = labels
+            .iter()
+            .filter(|&&label| label != usize::MAX)
+            .max()
+            .map_or(0, |&max| max + 1);
+        self.split(&labels, count)
+    }
+
+    /// Merge parts that share one id space (e.g. from `into_components`) back into one DAG
+    ///
+    /// Nodes, ids and edges are kept exactly; the first part's policy is used.
+    pub fn rejoin<I>(parts: I) -> Result<DAG<T>, RejoinError>
+    where
+        I: IntoIterator<Item = DAG<T>>,
+    {
+        let mut parts = parts.into_iter();
+        let Some(first) = parts.next() else {
+            return Ok(DAG::new());
+        };
+        let policy = Arc::clone(&first.policy);
+        let mut origin_len = first.origin_len;
+        let mut slots = first.slots;
+
+        for part in parts {
+            origin_len = origin_len.max(part.origin_len);
+            if part.slots.len() > slots.len() {
+                slots.resize_with(part.slots.len(), || Slot {
+                    generation: 0,
+                    node: None,
+                });
+            }
+            for (index, slot) in part.slots.into_iter().enumerate() {
+                let merged = &mut slots[index];
+                match (merged.node.is_some(), slot.node.is_some()) {
+                    (true, true) => {
+                        return Err(RejoinError::Overlap(NodeId {
+                            index: index as u32,
+                            generation: slot.generation,
+                        }))
+                    }
+                    (false, true) => *merged = slot,
+                    (true, false) => {}
+                    (false, false) => merged.generation = merged.generation.max(slot.generation),
+                }
+            }
+        }
+
+        let dag = DAG::from_part(slots, policy, origin_len);
+        for id in dag.ids() {
+            if let Some(&missing) = dag.children(id).iter().find(|&&child| !dag.contains(child)) {
+                return Err(RejoinError::Edge(EdgeError::UnknownNode(missing)));
+            }
+        }
+        Ok(dag)
+    }
+
+    /// Component label per slot index (`usize::MAX` for vacant slots)
+    fn slot_labels(&self) -> Vec<usize> {
+        let mut sets = UnionFind::new(self.slots.len());
+        for id in self.ids() {
+            for child in self.children(id) {
+                sets.union(id.index(), child.index());
+            }
+        }
+
+        // Number representatives in order of first appearance
+        let mut labels = vec![usize::MAX; self.slots.len()];
+        let mut numbering: BTreeMap<usize, usize> = BTreeMap::new();
+        for id in self.ids() {
+            let next = numbering.len();
+            let label = *numbering.entry(sets.find(id.index())).or_insert(next);
+            labels[id.index()] = label;
+        }
+        labels
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// 16 nodes: chain 0 → … → 4, edge 5 → 6, the rest isolated
+    fn sample() -> (DAG<usize>, Vec<NodeId>) {
+        let mut dag = DAG::new();
+        let ids: Vec<NodeId> = (0..16).map(|i| dag.add_node(i).unwrap()).collect();
+        for pair in ids[..5].windows(2) {
+            dag.add_edge(pair[0], pair[1]).unwrap();
+        }
+        dag.add_edge(ids[5], ids[6]).unwrap();
+        (dag, ids)
+    }
+
+    #[test]
+    fn union_find_merges_sets() {
+        let mut sets = UnionFind::new(4);
+        assert!(sets.union(0, 1));
+        assert!(sets.union(2, 3));
+        assert!(!sets.union(1, 0));
+        assert_eq!(sets.find(0), sets.find(1));
+        assert_ne!(sets.find(1), sets.find(2));
+        assert!(sets.union(1, 3));
+        assert_eq!(sets.find(0), sets.find(2));
+    }
+
+    #[test]
+    fn labels_follow_smallest_id() {
+        let (dag, ids) = sample();
+        let labels = dag.component_labels();
+
+        assert_eq!(labels[&ids[0]], 0);
+        assert_eq!(labels[&ids[4]], 0);
+        assert_eq!(labels[&ids[5]], 1);
+        assert_eq!(labels[&ids[6]], 1);
+        assert_eq!(labels[&ids[15]], 10);
+    }
+
+    #[test]
+    fn components_are_independently_executable() {
+        let (dag, _) = sample();
+        assert!(dag.traverse(|_| {}).is_ok());
+
+        for part in dag.components().into_iter().chain(dag.into_components()) {
+            let mut visited = 0;
+            assert!(part.traverse(|_| visited += 1).is_ok());
+            assert_eq!(visited, part.len());
+        }
+    }
+
+    #[test]
+    fn rejoin_restores_ids_and_edges() {
+        let (dag, ids) = sample();
+        let edges: Vec<(NodeId, Vec<NodeId>)> = dag
+            .ids()
+            .map(|id| (id, dag.children(id).to_vec()))
+            .collect();
+
+        let parts = dag.into_components();
+        assert_eq!(parts.len(), 11);
+        let rejoined = DAG::rejoin(parts).unwrap();
+
+        let restored: Vec<(NodeId, Vec<NodeId>)> = rejoined
+            .ids()
+            .map(|id| (id, rejoined.children(id).to_vec()))
+            .collect();
+        assert_eq!(restored, edges);
+        assert_eq!(rejoined[ids[3]], 3);
+    }
+
+    #[test]
+    fn rejoin_rejects_overlapping_parts() {
+        let (dag, ids) = sample();
+        let part = dag.subgraph([ids[0]]);
+        let again = dag.subgraph([ids[0], ids[1]]);
+
+        assert_eq!(
+            DAG::rejoin([part, again]).err(),
+            Some(RejoinError::Overlap(ids[0]))
+        );
+    }
+}
@@ -0,0 +1,257 @@
+// core/dag-engine/equivalence/mod.rs
+
+use std::collections::hash_map::DefaultHasher;
+use std::collections::{BTreeMap, HashMap};
+use std::hash::{Hash, Hasher};
+
+use super::{NodeId, DAG};
+
+impl<T> DAG<T> {
+    /// Witness mapping `self → other` if the two DAGs are isomorphic
+    ///
+    /// Every node maps to a node `node_eq` accepts, and `a → b` is an edge
+    /// here exactly when `map[a] → map[b]` is an edge in `other`. Pass
+    /// `|_, _| true` to compare shape alone. Candidates are pruned by a
+    /// structural signature of each node's ancestry and descendancy, then
+    /// matched VF2-style in topological order with backtracking.
+    pub fn is_isomorphic<U, F>(
+        &self,
+        other: &DAG<U>,
+        mut node_eq: F,
+    ) -> Option<BTreeMap<NodeId, NodeId>>
+    where
+        F: FnMut(&T, &U) -> bool,
+    {
+        if self.len != other.len || self.edge_count() != other.edge_count() {
+            return None;
+        }
+
+        let (left, right) = (self.signatures(), other.signatures());
+        let mut left_counts: Vec<u64> = self.ids().map(|id| left[id.index()]).collect();
+        let mut right_counts: Vec<u64> = other.ids().map(|id| right[id.index()]).collect();
+        left_counts.sort_unstable();
+        right_counts.sort_unstable();
+        if left_counts != right_counts {
+            return None;
+        }
+
+        let mut by_signature: HashMap<u64, Vec<NodeId>> = HashMap::new();
+        for id in other.ids() {
+            by_signature.entry(right[id.index()]).or_default().push(id);
+        }
+
+        // Parents precede children, so a node's parents are always mapped first
+        let order: Vec<NodeId> = self.topological_order().map(|(id, _)| id).collect();
+        let mut candidates = Vec::with_capacity(order.len());
+        for &id in &order {
+            let matching: Vec<NodeId> = by_signature[&left[id.index()]]
+                .iter()
+                .copied()
+                .filter(|&target| node_eq(&self[id], &other[target]))
+                .collect();
+            if matching.is_empty() {
+                return None;
+            }
+            candidates.push(matching);
+        }
+
+        let (left_parents, right_parents) = (self.parent_lists(), other.parent_lists());
+        let mut mapping: Vec<Option<NodeId>> = vec![None; self.slots.len()];
+        let mut used = vec![false; other.slots.len()];
+        let mut next = vec![0usize; order.len()]; // Next candidate to try at each position
+        let mut position = 0;
+
+        while position < order.len() {
+            let id = order[position];
+            let mut placed = false;
+
+            while let Some(&target) = candidates[position].get(next[position]) {
+                next[position] += 1;
+                if used[target.index()] {
+                    continue;
+                }
+
+                // Edges into `id` must map onto exactly the edges into `target`
+                let mut images: Vec<NodeId> = left_parents[id.index()]
+                    .iter()
+                    .map(|parent| mapping[parent.index()].expect("parents are mapped first"))
+                    .collect();
+                images.sort_unstable();
+                if images == right_parents[target.index()] {
+                    mapping[id.index()] = Some(target);
+                    used[target.index()] = true;
+                    placed = true;
+                    break;
+                }
+            }
+
+            if placed {
+                position += 1;
+                if let Some(slot) = next.get_mut(position) {
+                    *slot = 0;
+                }
+                continue;
+            }
+
+            // Exhausted: undo the previous position and try its next candidate
+            if position == 0 {
+                return None;
+            }
+            position -= 1;
+            let undone = order[position];
+            let target = mapping[undone.index()]
+                .take()
+                .expect("earlier positions are mapped");
+            used[target.index()] = false;
+        }
+
+        Some(
+            order
+                .into_iter()
+                .map(|id| (id, mapping[id.index()].expect("every node is mapped")))
+                .collect(),
+        )
+    }
+
+    /// Number of edges
+    fn edge_count(&self) -> usize {
+        self.ids().map(|id| self.children(id).len()).sum()
+    }
+
+    /// Parents of every slot, sorted by id
+    fn parent_lists(&self) -> Vec<Vec<NodeId>> {
+        let mut parents = vec![Vec::new(); self.slots.len()];
+        for id in self.ids() {
+            for child in self.children(id) {
+                parents[child.index()].push(id);
+            }
+        }
+        parents
+    }
+
+    /// Isomorphism-invariant signature of every slot
+    ///
+    /// Combines a hash of the node's ancestry (built root-down from sorted
+    /// parent hashes) with one of its descendancy (built leaf-up from sorted
+    /// child hashes). Isomorphic nodes always agree; a collision only costs
+    /// extra backtracking.
+    fn signatures(&self) -> Vec<u64> {
+        let order: Vec<NodeId> = self.topological_order().map(|(id, _)| id).collect();
+        let parents = self.parent_lists();
+
+        let mut down = vec![0u64; self.slots.len()];
+        for &id in &order {
+            down[id.index()] = combine(0, parents[id.index()].iter().map(|p| down[p.index()]));
+        }
+
+        let mut up = vec![0u64; self.slots.len()];
+        for &id in order.iter().rev() {
+            up[id.index()] = combine(1, self.children(id).iter().map(|c| up[c.index()]));
+        }
+
+        let mut signatures = vec![0u64; self.slots.len()];
+        for &id in &order {
+            let mut hasher = DefaultHasher::new();
+            (down[id.index()], up[id.index()]).hash(&mut hasher);
+            signatures[id.index()] = hasher.finish();
+        }
+        signatures
+    }
+}
+
+/// Hash of a direction tag and the sorted multiset of neighbour hashes
+fn combine<I>(direction: u8, neighbours: I) -> u64
+where
+    I: Iterator<Item = u64>,
+{
+    let mut hashes: Vec<u64> = neighbours.collect();
+    hashes.sort_unstable();
+
+    let mut hasher = DefaultHasher::new();
+    direction.hash(&mut hasher);
+    hashes.hash(&mut hasher);
+    hasher.finish()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::FixedCap;
+
+    fn build(values: &[char], edges: &[(usize, usize)]) -> (DAG<char>, Vec<NodeId>) {
+        let mut dag = DAG::with_policy(FixedCap(8));
+        let ids: Vec<NodeId> = values.iter().map(|&v| dag.add_node(v).unwrap()).collect();
+        for &(from, to) in edges {
+            dag.add_edge(ids[from], ids[to]).unwrap();
+        }
+        (dag, ids)
+    }
+
+    /// `map` is a bijection carrying every edge of `a` onto one of `b` and back
+    fn assert_witness(a: &DAG<char>, b: &DAG<char>, map: &BTreeMap<NodeId, NodeId>) {
+        assert_eq!(map.len(), a.len());
+        let mut images: Vec<NodeId> = map.values().copied().collect();
+        images.sort();
+        images.dedup();
+        assert_eq!(images.len(), b.len());
+        for from in a.ids() {
+            for to in a.ids() {
+                assert_eq!(a.has_edge(from, to), b.has_edge(map[&from], map[&to]));
+            }
+        }
+    }
+
+    #[test]
+    fn witness_maps_edges_onto_edges() {
+        // Diamond with an extra leaf, inserted in a different order on each side
+        let (a, _) = build(
+            &['r', 'x', 'y', 'j', 'l'],
+            &[(0, 1), (0, 2), (1, 3), (2, 3), (1, 4)],
+        );
+        let (b, ids) = build(
+            &['l', 'j', 'y', 'x', 'r'],
+            &[(4, 3), (4, 2), (3, 1), (2, 1), (3, 0)],
+        );
+
+        let map = a.is_isomorphic(&b, |_, _| true).unwrap();
+        assert_witness(&a, &b, &map);
+
+        let by_data = a.is_isomorphic(&b, |x, y| x == y).unwrap();
+        assert_witness(&a, &b, &by_data);
+        assert!(a.ids().all(|id| a[id] == b[by_data[&id]]));
+        assert_eq!(by_data[&a.ids().next().unwrap()], ids[4]);
+    }
+
+    #[test]
+    fn data_mismatch_rules_out_a_shape_match() {
+        let (a, _) = build(&['a', 'b'], &[(0, 1)]);
+        let (b, _) = build(&['a', 'c'], &[(0, 1)]);
+
+        assert!(a.is_isomorphic(&b, |_, _| true).is_some());
+        assert!(a.is_isomorphic(&b, |x, y| x == y).is_none());
+    }
+
+    #[test]
+    fn equal_counts_with_different_shapes_are_rejected() {
+        // Both have 4 nodes and 3 edges: a chain against a star
+        let (chain, _) = build(&['a'; 4], &[(0, 1), (1, 2), (2, 3)]);
+        let (star, _) = build(&['a'; 4], &[(0, 1), (0, 2), (0, 3)]);
+        assert!(chain.is_isomorphic(&star, |_, _| true).is_none());
+
+        // Same two joins, but one of them feeds both leaves
+        let (a, _) = build(&['a'; 6], &[(0, 2), (1, 2), (0, 3), (1, 3), (2, 4), (3, 5)]);
+        let (b, _) = build(&['a'; 6], &[(0, 2), (0, 3), (1, 2), (1, 3), (2, 4), (2, 5)]);
+        assert!(a.is_isomorphic(&b, |_, _| true).is_none());
+    }
+
+    #[test]
+    fn backtracks_out_of_a_wrong_early_choice() {
+        // Two identical-looking roots; only one pairing fits the leaf data
+        let (a, _) = build(&['r', 'r', 'p', 'q'], &[(0, 2), (1, 3)]);
+        let (b, ids) = build(&['r', 'r', 'q', 'p'], &[(0, 2), (1, 3)]);
+
+        let map = a.is_isomorphic(&b, |x, y| x == y).unwrap();
+        assert_witness(&a, &b, &map);
+        assert_eq!(map[&a.ids().next().unwrap()], ids[1]);
+    }
+}
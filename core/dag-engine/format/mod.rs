@@ -0,0 +1,375 @@
+// core/dag-engine/format/mod.rs
+
+use std::collections::BTreeMap;
+use std::fmt;
+use std::sync::Arc;
+
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+
+use super::{DepthPolicy, EdgeError, Node, NodeId, PolicySpec, Slot, DAG};
+
+/// Schema version written by this build; loading any other version fails
+pub const FORMAT_VERSION: u32 = 1;
+
+/// Versioned, serializable snapshot of a DAG
+///
+/// Every slot is listed, live or vacant, so node ids survive a round trip.
+/// Nodes and edges are written in id order, keeping text snapshots diffable.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DagDocument<T> {
+    pub version: u32,
+    pub policy: PolicySpec,
+    pub metadata: BTreeMap<String, String>,
+    /// Node count the depth bound is computed for; exceeds `nodes.len()` for
+    /// a part cut from a larger graph or after removals
+    pub node_count: usize,
+    pub nodes: Vec<NodeRecord<T>>,
+    pub edges: Vec<EdgeRecord>,
+    /// Vacant slots with the generation their next node will get
+    pub vacant: Vec<NodeId>,
+}
+
+/// Live node of a [`DagDocument`]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct NodeRecord<T> {
+    pub id: NodeId,
+    pub data: T,
+}
+
+/// Dependency edge `from → to` of a [`DagDocument`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct EdgeRecord {
+    pub from: NodeId,
+    pub to: NodeId,
+}
+
+/// Failure to encode, decode or re-validate a serialized DAG
+#[derive(Debug)]
+pub enum FormatError {
+    Json(serde_json::Error),
+    Binary(bincode::Error),
+    /// Document was written under another schema version
+    UnsupportedVersion {
+        found: u32,
+        supported: u32,
+    },
+    /// Two records claim the same slot
+    DuplicateSlot(NodeId),
+    /// Slot index past the number of listed slots
+    SlotOutOfRange(NodeId),
+    /// Document stores a `Custom` policy; load with `into_dag_with_policy`
+    MissingPolicy,
+    /// Edge rejected on reload (unknown node, cycle or depth bound)
+    Edge(EdgeError),
+}
+
+/// Leading field of every document, read before the full schema
+#[derive(Deserialize)]
+struct Header {
+    version: u32,
+}
+
+impl From<EdgeError> for FormatError {
+    fn from(error: EdgeError) -> Self {
+        FormatError::Edge(error)
+    }
+}
+
+impl fmt::Display for FormatError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            FormatError::Json(error) => write!(f, "invalid JSON document: {error}"),
+            FormatError::Binary(error) => write!(f, "invalid binary document: {error}"),
+            FormatError::UnsupportedVersion { found, supported } => write!(
+                f,
+                "document format version {found} is not supported (expected {supported})"
+            ),
+            FormatError::DuplicateSlot(id) => write!(f, "slot of node {id} is listed twice"),
+            FormatError::SlotOutOfRange(id) => {
+                write!(f, "node {id} lies outside the listed slots")
+            }
+            FormatError::MissingPolicy => {
+                write!(
+                    f,
+                    "document has a custom depth policy that must be supplied"
+                )
+            }
+            FormatError::Edge(error) => write!(f, "document rejected: {error}"),
+        }
+    }
+}
+
+impl std::error::Error for FormatError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            FormatError::Json(error) => Some(error),
+            FormatError::Binary(error) => Some(error),
+            FormatError::Edge(error) => Some(error),
+            _ => None,
+        }
+    }
+}
+
+impl<T: Serialize> DagDocument<T> {
+    /// Encode as pretty-printed JSON
+    pub fn to_json(&self) -> Result<String, FormatError> {
+        serde_json::to_string_pretty(self).map_err(FormatError::Json)
+    }
+
+    /// Encode in the compact binary form (bincode)
+    pub fn to_binary(&self) -> Result<Vec<u8>, FormatError> {
+        bincode::serialize(self).map_err(FormatError::Binary)
+    }
+}
+
+impl<T: DeserializeOwned> DagDocument<T> {
+    /// Decode JSON, checking the schema version first
+    pub fn from_json(text: &str) -> Result<Self, FormatError> {
+        let header: Header = serde_json::from_str(text).map_err(FormatError::Json)?;
+        check_version(header.version)?;
+        serde_json::from_str(text).map_err(FormatError::Json)
+    }
+
+    /// Decode the binary form, checking the schema version first
+    pub fn from_binary(bytes: &[u8]) -> Result<Self, FormatError> {
+        let version: u32 = bincode::deserialize(bytes).map_err(FormatError::Binary)?;
+        check_version(version)?;
+        bincode::deserialize(bytes).map_err(FormatError::Binary)
+    }
+}
+
+impl<T> DagDocument<T> {
+    /// Rebuild the DAG under the stored policy, restoring its metadata
+    ///
+    /// Every edge is re-added through `add_edge`, so cycles and depth bound
+    /// violations are rejected exactly as on a live DAG.
+    pub fn into_dag(self) -> Result<DAG<T>, FormatError> {
+        let policy = self.policy.build().ok_or(FormatError::MissingPolicy)?;
+        self.rebuild(policy)
+    }
+
+    /// Rebuild the DAG under `policy`, ignoring the stored one
+    pub fn into_dag_with_policy<P: DepthPolicy + 'static>(
+        self,
+        policy: P,
+    ) -> Result<DAG<T>, FormatError> {
+        self.rebuild(Arc::new(policy))
+    }
+
+    fn rebuild(self, policy: Arc<dyn DepthPolicy>) -> Result<DAG<T>, FormatError> {
+        check_version(self.version)?;
+
+        let count = self.nodes.len() + self.vacant.len();
+        let mut slots: Vec<Option<Slot<T>>> = (0..count).map(|_| None).collect();
+        let records = self.vacant.into_iter().map(|id| (id, None)).chain(
+            self.nodes
+                .into_iter()
+                .map(|node| (node.id, Some(node.data))),
+        );
+
+        for (id, data) in records {
+            let slot = slots
+                .get_mut(id.index())
+                .ok_or(FormatError::SlotOutOfRange(id))?;
+            if slot.is_some() {
+                return Err(FormatError::DuplicateSlot(id));
+            }
+            *slot = Some(Slot {
+                generation: id.generation,
+                node: data.map(|data| Node {
+                    data,
+                    edges: Vec::new(),
+                }),
+            });
+        }
+
+        let slots = slots
+            .into_iter()
+            .map(|slot| slot.expect("every slot is listed once"))
+            .collect();
+        let mut dag = DAG::from_part(slots, policy, self.node_count);
+        dag.metadata = self.metadata;
+        for edge in self.edges {
+            dag.add_edge(edge.from, edge.to)?;
+        }
+        Ok(dag)
+    }
+}
+
+impl<T> DAG<T> {
+    /// Snapshot this DAG as a document borrowing its node data
+    pub fn to_document(&self) -> DagDocument<&T> {
+        let mut document = DagDocument {
+            version: FORMAT_VERSION,
+            policy: self.policy.spec(),
+            metadata: self.metadata.clone(),
+            node_count: self.bound_len(),
+            nodes: Vec::with_capacity(self.len),
+            edges: Vec::new(),
+            vacant: Vec::with_capacity(self.slots.len() - self.len),
+        };
+
+        for (index, slot) in self.slots.iter().enumerate() {
+            let id = NodeId {
+                index: index as u32,
+                generation: slot.generation,
+            };
+            let Some(node) = slot.node.as_ref() else {
+                document.vacant.push(id);
+                continue;
+            };
+            document.nodes.push(NodeRecord {
+                id,
+                data: &node.data,
+            });
+            document
+                .edges
+                .extend(node.edges.iter().map(|&to| EdgeRecord { from: id, to }));
+        }
+
+        document
+    }
+
+    /// Encode as a pretty-printed JSON document
+    pub fn to_json(&self) -> Result<String, FormatError>
+    where
+        T: Serialize,
+    {
+        self.to_document().to_json()
+    }
+
+    /// Encode as a compact binary document
+    pub fn to_binary(&self) -> Result<Vec<u8>, FormatError>
+    where
+        T: Serialize,
+    {
+        self.to_document().to_binary()
+    }
+
+    /// Decode and re-validate a JSON document
+    pub fn from_json(text: &str) -> Result<Self, FormatError>
+    where
+        T: DeserializeOwned,
+    {
+        DagDocument::from_json(text)?.into_dag()
+    }
+
+    /// Decode and re-validate a binary document
+    pub fn from_binary(bytes: &[u8]) -> Result<Self, FormatError>
+    where
+        T: DeserializeOwned,
+    {
+        DagDocument::from_binary(bytes)?.into_dag()
+    }
+}
+
+fn check_version(found: u32) -> Result<(), FormatError> {
+    if found != FORMAT_VERSION {
+        return Err(FormatError::UnsupportedVersion {
+            found,
+            supported: FORMAT_VERSION,
+        });
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{CustomBound, FixedCap};
+
+    /// Three nodes with a vacant slot between them
+    fn sample() -> (DAG<String>, Vec<NodeId>) {
+        let mut dag = DAG::with_policy(FixedCap(4));
+        let ids: Vec<NodeId> = ["a", "b", "c"]
+            .into_iter()
+            .map(|name| dag.add_node(name.to_string()).unwrap())
+            .collect();
+        dag.add_edge(ids[0], ids[2]).unwrap();
+        dag.remove_node(ids[1]);
+        dag.metadata_mut()
+            .insert("owner".to_string(), "hdis".to_string());
+        (dag, ids)
+    }
+
+    #[test]
+    fn json_round_trip_keeps_ids_edges_and_metadata() {
+        let (dag, ids) = sample();
+        let text = dag.to_json().unwrap();
+        let loaded: DAG<String> = DAG::from_json(&text).unwrap();
+
+        assert_eq!(loaded.ids().collect::<Vec<_>>(), vec![ids[0], ids[2]]);
+        assert_eq!(loaded.children(ids[0]), &[ids[2]]);
+        assert_eq!(loaded[ids[2]], "c");
+        assert_eq!(loaded.metadata(), dag.metadata());
+        assert_eq!(loaded.policy().spec(), PolicySpec::FixedCap { cap: 4 });
+        assert_eq!(loaded.to_document().vacant, dag.to_document().vacant);
+    }
+
+    #[test]
+    fn binary_round_trip_matches_json() {
+        let (dag, _) = sample();
+        let loaded: DAG<String> = DAG::from_binary(&dag.to_binary().unwrap()).unwrap();
+        assert_eq!(loaded.to_json().unwrap(), dag.to_json().unwrap());
+    }
+
+    #[test]
+    fn other_versions_are_rejected() {
+        let (dag, _) = sample();
+        let mut document = dag.to_document();
+        document.version = FORMAT_VERSION + 1;
+        let text = document.to_json().unwrap();
+
+        assert!(matches!(
+            DAG::<String>::from_json(&text),
+            Err(FormatError::UnsupportedVersion { found, .. }) if found == FORMAT_VERSION + 1
+        ));
+    }
+
+    #[test]
+    fn custom_policy_must_be_supplied() {
+        let mut dag = DAG::with_policy(CustomBound(|n: usize| n));
+        dag.add_node(1u8).unwrap();
+        let text = dag.to_json().unwrap();
+
+        assert!(matches!(
+            DAG::<u8>::from_json(&text),
+            Err(FormatError::MissingPolicy)
+        ));
+        let document: DagDocument<u8> = DagDocument::from_json(&text).unwrap();
+        assert_eq!(document.into_dag_with_policy(FixedCap(1)).unwrap().len(), 1);
+    }
+
+    #[test]
+    fn cyclic_edges_are_rejected_on_load() {
+        let (dag, ids) = sample();
+        let mut document = dag.to_document();
+        document.edges.push(EdgeRecord {
+            from: ids[2],
+            to: ids[0],
+        });
+        let text = document.to_json().unwrap();
+
+        assert!(matches!(
+            DAG::<String>::from_json(&text),
+            Err(FormatError::Edge(EdgeError::Cycle { .. }))
+        ));
+    }
+
+    #[test]
+    fn component_round_trip_keeps_the_parent_bound() {
+        // Five-node chain among sixteen nodes: depth 4, within ceil(log2 16)
+        let mut dag = DAG::new();
+        let ids: Vec<NodeId> = (0..16).map(|i| dag.add_node(i).unwrap()).collect();
+        for pair in ids[..5].windows(2) {
+            dag.add_edge(pair[0], pair[1]).unwrap();
+        }
+
+        let chain = dag.components().remove(0);
+        assert_eq!(chain.to_document().node_count, 16);
+        let loaded: DAG<i32> = DAG::from_json(&chain.to_json().unwrap()).unwrap();
+        assert_eq!(loaded.ids().collect::<Vec<_>>(), ids[..5].to_vec());
+        assert_eq!(loaded.to_json().unwrap(), chain.to_json().unwrap());
+    }
+}
@@ -0,0 +1,284 @@
+// core/dag-engine/incremental/mod.rs
+
+use std::collections::hash_map::DefaultHasher;
+use std::collections::{BTreeMap, BTreeSet};
+use std::hash::{Hash, Hasher};
+
+use super::{BitSet, NodeId, DAG};
+
+/// Cached node outputs and dirty marks for [`DAG::execute_incremental`]
+///
+/// The cache is keyed by `NodeId`, so it stays valid across edits to the DAG
+/// it was built from: removed nodes are pruned, new nodes run on first use.
+pub struct IncrementalCache<R> {
+    entries: BTreeMap<NodeId, Cached<R>>,
+    dirty: BTreeSet<NodeId>,
+}
+
+/// Output of one node with the hashes that decide whether it is reusable
+struct Cached<R> {
+    parents: Vec<NodeId>,
+    input_hash: u64, // Node data plus every parent's output hash
+    output_hash: u64,
+    output: R,
+}
+
+impl<R> Default for IncrementalCache<R> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<R> IncrementalCache<R> {
+    /// Empty cache: the first run executes every node
+    pub fn new() -> Self {
+        Self {
+            entries: BTreeMap::new(),
+            dirty: BTreeSet::new(),
+        }
+    }
+
+    /// Re-check `id` on the next run
+    ///
+    /// Call after changing a node's data; edge changes are detected on their
+    /// own. If the node's content hash is unchanged the re-check is a no-op.
+    pub fn mark_dirty(&mut self, id: NodeId) {
+        self.dirty.insert(id);
+    }
+
+    /// True when `id` will be re-checked on the next run
+    pub fn is_dirty(&self, id: NodeId) -> bool {
+        self.dirty.contains(&id)
+    }
+
+    /// Cached output of a node
+    pub fn output(&self, id: NodeId) -> Option<&R> {
+        self.entries.get(&id).map(|cached| &cached.output)
+    }
+
+    /// Drop every cached output, forcing a full run
+    pub fn clear(&mut self) {
+        self.entries.clear();
+        self.dirty.clear();
+    }
+}
+
+impl<T> DAG<T> {
+    /// Run `f` in dependency order on dirty nodes, new nodes, nodes whose
+    /// parents changed and whatever depends on a changed output; every other
+    /// node keeps its cached output
+    ///
+    /// `f` receives the node and its parents' outputs in parent id order. A
+    /// node is skipped when the hash of its data and inputs matches the cache,
+    /// and its descendants are skipped when its new output hashes the same as
+    /// before. Returns the executed nodes in order. On error the failing node
+    /// and everything downstream of a changed output stay dirty.
+    pub fn execute_incremental<R, E, F>(
+        &self,
+        cache: &mut IncrementalCache<R>,
+        mut f: F,
+    ) -> Result<Vec<NodeId>, E>
+    where
+        T: Hash,
+        R: Hash,
+        F: FnMut(NodeId, &T, &[&R]) -> Result<R, E>,
+    {
+        cache.entries.retain(|&id, _| self.contains(id));
+        cache.dirty.retain(|&id| self.contains(id));
+
+        let mut parents: Vec<Vec<NodeId>> = vec![Vec::new(); self.slots.len()];
+        for id in self.ids() {
+            for child in self.children(id) {
+                parents[child.index()].push(id);
+            }
+        }
+
+        let mut changed = BitSet::new(self.slots.len());
+        let mut changed_ids = Vec::new();
+        let mut executed = Vec::new();
+
+        for (id, data) in self.topological_order() {
+            let inputs = &parents[id.index()];
+            let stale = match cache.entries.get(&id) {
+                None => true,
+                Some(cached) => {
+                    cache.dirty.contains(&id)
+                        || cached.parents != *inputs
+                        || inputs.iter().any(|parent| changed.contains(parent.index()))
+                }
+            };
+            if !stale {
+                continue;
+            }
+
+            let mut hasher = DefaultHasher::new();
+            data.hash(&mut hasher);
+            for parent in inputs {
+                cache.entries[parent].output_hash.hash(&mut hasher);
+            }
+            let input_hash = hasher.finish();
+
+            let previous = cache.entries.get(&id).map(|cached| cached.output_hash);
+            if let Some(cached) = cache.entries.get_mut(&id) {
+                if cached.input_hash == input_hash {
+                    cached.parents.clone_from(inputs);
+                    cache.dirty.remove(&id);
+                    continue;
+                }
+            }
+
+            let values: Vec<&R> = inputs
+                .iter()
+                .map(|parent| &cache.entries[parent].output)
+                .collect();
+            let output = match f(id, data, &values) {
+                Ok(output) => output,
+                Err(error) => {
+                    cache.dirty.insert(id);
+                    for &source in &changed_ids {
+                        cache.dirty.extend(self.children(source).iter().copied());
+                    }
+                    return Err(error);
+                }
+            };
+
+            let mut hasher = DefaultHasher::new();
+            output.hash(&mut hasher);
+            let output_hash = hasher.finish();
+            if previous != Some(output_hash) {
+                changed.insert(id.index());
+                changed_ids.push(id);
+            }
+
+            cache.entries.insert(
+                id,
+                Cached {
+                    parents: inputs.clone(),
+                    input_hash,
+                    output_hash,
+                    output,
+                },
+            );
+            cache.dirty.remove(&id);
+            executed.push(id);
+        }
+
+        Ok(executed)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::FixedCap;
+
+    /// Diamond a → {b, c} → d, each node summing its data and inputs
+    fn diamond() -> (DAG<i64>, Vec<NodeId>) {
+        let mut dag = DAG::with_policy(FixedCap(8));
+        let ids: Vec<NodeId> = [1, 10, 100, 1000]
+            .into_iter()
+            .map(|value| dag.add_node(value).unwrap())
+            .collect();
+        for (from, to) in [(0, 1), (0, 2), (1, 3), (2, 3)] {
+            dag.add_edge(ids[from], ids[to]).unwrap();
+        }
+        (dag, ids)
+    }
+
+    fn run(dag: &DAG<i64>, cache: &mut IncrementalCache<i64>) -> Vec<NodeId> {
+        dag.execute_incremental(cache, |_, &value, inputs: &[&i64]| {
+            Ok::<_, ()>(value + inputs.iter().copied().sum::<i64>())
+        })
+        .unwrap()
+    }
+
+    #[test]
+    fn first_run_executes_everything_then_nothing() {
+        let (dag, ids) = diamond();
+        let mut cache = IncrementalCache::new();
+
+        assert_eq!(run(&dag, &mut cache), ids);
+        assert_eq!(cache.output(ids[3]), Some(&1112));
+        assert!(run(&dag, &mut cache).is_empty());
+    }
+
+    #[test]
+    fn dirty_node_reruns_itself_and_its_dependents() {
+        let (mut dag, ids) = diamond();
+        let mut cache = IncrementalCache::new();
+        run(&dag, &mut cache);
+
+        *dag.get_mut(ids[1]).unwrap() = 20;
+        cache.mark_dirty(ids[1]);
+        assert!(cache.is_dirty(ids[1]));
+        assert_eq!(run(&dag, &mut cache), vec![ids[1], ids[3]]);
+        assert_eq!(cache.output(ids[3]), Some(&1122));
+        assert!(!cache.is_dirty(ids[1]));
+    }
+
+    #[test]
+    fn unchanged_content_is_not_rerun() {
+        let (dag, ids) = diamond();
+        let mut cache = IncrementalCache::new();
+        run(&dag, &mut cache);
+
+        cache.mark_dirty(ids[0]);
+        assert!(run(&dag, &mut cache).is_empty());
+        assert!(!cache.is_dirty(ids[0]));
+    }
+
+    #[test]
+    fn equal_output_stops_propagation() {
+        let (mut dag, ids) = diamond();
+        let mut cache = IncrementalCache::new();
+        let parity = |dag: &DAG<i64>, cache: &mut IncrementalCache<i64>| {
+            dag.execute_incremental(cache, |_, &value, inputs: &[&i64]| {
+                Ok::<_, ()>((value + inputs.iter().copied().sum::<i64>()) % 2)
+            })
+            .unwrap()
+        };
+        parity(&dag, &mut cache);
+
+        *dag.get_mut(ids[1]).unwrap() = 12;
+        cache.mark_dirty(ids[1]);
+        assert_eq!(parity(&dag, &mut cache), vec![ids[1]]);
+    }
+
+    #[test]
+    fn edge_edits_and_removals_are_detected() {
+        let (mut dag, ids) = diamond();
+        let mut cache = IncrementalCache::new();
+        run(&dag, &mut cache);
+
+        assert!(dag.remove_edge(ids[2], ids[3]));
+        assert_eq!(run(&dag, &mut cache), vec![ids[3]]);
+        assert_eq!(cache.output(ids[3]), Some(&1011));
+
+        dag.remove_node(ids[2]);
+        assert!(run(&dag, &mut cache).is_empty());
+        assert_eq!(cache.output(ids[2]), None);
+    }
+
+    #[test]
+    fn failure_leaves_the_failing_node_and_dependents_dirty() {
+        let (mut dag, ids) = diamond();
+        let mut cache = IncrementalCache::new();
+        run(&dag, &mut cache);
+
+        *dag.get_mut(ids[0]).unwrap() = 2;
+        cache.mark_dirty(ids[0]);
+        let result = dag.execute_incremental(&mut cache, |id, &value, inputs: &[&i64]| {
+            if id == ids[2] {
+                return Err("c failed");
+            }
+            Ok(value + inputs.iter().copied().sum::<i64>())
+        });
+
+        assert_eq!(result, Err("c failed"));
+        assert!(cache.is_dirty(ids[2]));
+        assert!(cache.is_dirty(ids[3]));
+        assert_eq!(cache.output(ids[1]), Some(&12));
+        assert_eq!(run(&dag, &mut cache), vec![ids[2], ids[3]]);
+        assert_eq!(cache.output(ids[3]), Some(&1114));
+    }
+}
@@ -0,0 +1,216 @@
+// core/dag-engine/isomorphic/mod.rs
+
+use std::collections::BTreeMap;
+use std::fmt;
+
+use super::{DepthPolicy, DepthViolation, EdgeError, NodeId, DAG};
+
+/// Partition label of an isomorphic DAG
+pub type PartitionId = u32;
+
+/// Node data tagged with the partition it belongs to
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Part<T> {
+    pub partition: PartitionId,
+    pub data: T,
+}
+
+/// Isomorphic DAG (Disjoint-based): edges never cross partitions
+///
+/// Every partition is an independent sub-DAG (⊔ of its parts), checked on insert.
+pub struct IsomorphicDag<T> {
+    dag: DAG<Part<T>>,
+}
+
+/// Disjointness failure
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PartitionError {
+    /// Edge would join two partitions
+    CrossPartition {
+        from: NodeId,
+        to: NodeId,
+        from_partition: PartitionId,
+        to_partition: PartitionId,
+    },
+    /// Edge rejected by the underlying DAG
+    Edge(EdgeError),
+}
+
+impl fmt::Display for PartitionError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            PartitionError::CrossPartition {
+                from,
+                to,
+                from_partition,
+                to_partition,
+            } => write!(
+                f,
+                "edge {from} → {to} would join partition {from_partition} to partition {to_partition}"
+            ),
+            PartitionError::Edge(error) => write!(f, "{error}"),
+        }
+    }
+}
+
+impl std::error::Error for PartitionError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            PartitionError::Edge(error) => Some(error),
+            _ => None,
+        }
+    }
+}
+
+impl From<EdgeError> for PartitionError {
+    fn from(error: EdgeError) -> Self {
+        PartitionError::Edge(error)
+    }
+}
+
+impl<T> Default for IsomorphicDag<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T> IsomorphicDag<T> {
+    /// Create new isomorphic DAG with the default depth bound
+    pub fn new() -> Self {
+        Self { dag: DAG::new() }
+    }
+
+    /// Create new isomorphic DAG whose depth bound is set by `policy`
+    pub fn with_policy<P: DepthPolicy + 'static>(policy: P) -> Self {
+        Self {
+            dag: DAG::with_policy(policy),
+        }
+    }
+
+    /// Partition a DAG with `partition_of`, failing on the first edge that crosses partitions
+    pub fn try_from_dag<F>(dag: DAG<T>, mut partition_of: F) -> Result<Self, PartitionError>
+    where
+        F: FnMut(NodeId, &T) -> PartitionId,
+    {
+        let mut by_slot = vec![0; dag.slots.len()];
+        for id in dag.ids() {
+            by_slot[id.index()] = partition_of(id, &dag[id]);
+        }
+
+        for from in dag.ids() {
+            for &to in dag.children(from) {
+                let (from_partition, to_partition) = (by_slot[from.index()], by_slot[to.index()]);
+                if from_partition != to_partition {
+                    return Err(PartitionError::CrossPartition {
+                        from,
+                        to,
+                        from_partition,
+                        to_partition,
+                    });
+                }
+            }
+        }
+
+        let dag = dag.map_nodes(|id, data| Part {
+            partition: by_slot[id.index()],
+            data,
+        });
+        Ok(Self { dag })
+    }
+
+    /// Add node to `partition`
+    pub fn add_node(&mut self, data: T, partition: PartitionId) -> Result<NodeId, DepthViolation> {
+        self.dag.add_node(Part { partition, data })
+    }
+
+    /// Add dependency edge `from → to` inside one partition
+    pub fn add_edge(&mut self, from: NodeId, to: NodeId) -> Result<(), PartitionError> {
+        if let (Some(source), Some(target)) = (self.dag.get(from), self.dag.get(to)) {
+            if source.partition != target.partition {
+                return Err(PartitionError::CrossPartition {
+                    from,
+                    to,
+                    from_partition: source.partition,
+                    to_partition: target.partition,
+                });
+            }
+        }
+
+        Ok(self.dag.add_edge(from, to)?)
+    }
+
+    /// Borrow node data
+    pub fn get(&self, id: NodeId) -> Option<&T> {
+        self.dag.get(id).map(|part| &part.data)
+    }
+
+    /// Partition of a node
+    pub fn partition(&self, id: NodeId) -> Option<PartitionId> {
+        self.dag.get(id).map(|part| part.partition)
+    }
+
+    /// Node ids of every partition, both in ascending order
+    pub fn partitions(&self) -> BTreeMap<PartitionId, Vec<NodeId>> {
+        let mut partitions: BTreeMap<PartitionId, Vec<NodeId>> = BTreeMap::new();
+        for id in self.dag.ids() {
+            partitions
+                .entry(self.dag[id].partition)
+                .or_default()
+                .push(id);
+        }
+        partitions
+    }
+
+    /// Underlying DAG of tagged nodes
+    pub fn dag(&self) -> &DAG<Part<T>> {
+        &self.dag
+    }
+
+    /// Unwrap into the underlying DAG of tagged nodes
+    pub fn into_dag(self) -> DAG<Part<T>> {
+        self.dag
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn edges_stay_inside_partitions() {
+        let mut dag = IsomorphicDag::new();
+        let a = dag.add_node("a", 0).unwrap();
+        let b = dag.add_node("b", 0).unwrap();
+        let c = dag.add_node("c", 1).unwrap();
+
+        dag.add_edge(a, b).unwrap();
+        assert_eq!(
+            dag.add_edge(b, c),
+            Err(PartitionError::CrossPartition {
+                from: b,
+                to: c,
+                from_partition: 0,
+                to_partition: 1
+            })
+        );
+        assert_eq!(
+            dag.partitions(),
+            BTreeMap::from([(0, vec![a, b]), (1, vec![c])])
+        );
+        assert_eq!(dag.partition(c), Some(1));
+        assert_eq!(dag.get(a), Some(&"a"));
+    }
+
+    #[test]
+    fn try_from_dag_rejects_crossing_edges() {
+        let mut dag = DAG::new();
+        let a = dag.add_node(0u32).unwrap();
+        let b = dag.add_node(1u32).unwrap();
+        dag.add_edge(a, b).unwrap();
+
+        assert!(matches!(
+            IsomorphicDag::try_from_dag(dag, |_, &data| data),
+            Err(PartitionError::CrossPartition { from, to, .. }) if (from, to) == (a, b)
+        ));
+    }
+}
@@ -0,0 +1,204 @@
+// core/dag-engine/lossless/mod.rs
+
+use std::fmt;
+
+use super::{DepthPolicy, DepthViolation, EdgeError, NodeId, DAG};
+
+/// Logical time of a merged state
+pub type Tick = u64;
+
+/// State paired with the tick it entered the DAG (Time ⊗ state)
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Stamped<T> {
+    pub at: Tick,
+    pub state: T,
+}
+
+/// Lossless DAG (Union-based): merging into a node keeps every source state
+///
+/// Each node holds its full history ordered by tick; nothing is overwritten.
+pub struct LosslessDag<T> {
+    dag: DAG<Vec<Stamped<T>>>,
+}
+
+/// Lossless property failure found by [`LosslessDag::try_from_dag`]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum LosslessError {
+    /// Node carries no state at all
+    EmptyHistory(NodeId),
+    /// History is not in tick order
+    OutOfOrder { node: NodeId, at: Tick, after: Tick },
+}
+
+impl fmt::Display for LosslessError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            LosslessError::EmptyHistory(node) => write!(f, "node {node} has no recorded state"),
+            LosslessError::OutOfOrder { node, at, after } => {
+                write!(f, "node {node} records tick {at} after tick {after}")
+            }
+        }
+    }
+}
+
+impl std::error::Error for LosslessError {}
+
+impl<T> Default for LosslessDag<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T> LosslessDag<T> {
+    /// Create new lossless DAG with the default depth bound
+    pub fn new() -> Self {
+        Self { dag: DAG::new() }
+    }
+
+    /// Create new lossless DAG whose depth bound is set by `policy`
+    pub fn with_policy<P: DepthPolicy + 'static>(policy: P) -> Self {
+        Self {
+            dag: DAG::with_policy(policy),
+        }
+    }
+
+    /// Wrap a DAG of histories, checking every node is non-empty and tick-ordered
+    pub fn try_from_dag(dag: DAG<Vec<Stamped<T>>>) -> Result<Self, LosslessError> {
+        for id in dag.ids() {
+            let history = &dag[id];
+            if history.is_empty() {
+                return Err(LosslessError::EmptyHistory(id));
+            }
+            if let Some(pair) = history.windows(2).find(|pair| pair[1].at < pair[0].at) {
+                return Err(LosslessError::OutOfOrder {
+                    node: id,
+                    at: pair[1].at,
+                    after: pair[0].at,
+                });
+            }
+        }
+
+        Ok(Self { dag })
+    }
+
+    /// Add node with its first state
+    pub fn add_node(&mut self, state: T, at: Tick) -> Result<NodeId, DepthViolation> {
+        self.dag.add_node(vec![Stamped { at, state }])
+    }
+
+    /// Add dependency edge `from → to`
+    pub fn add_edge(&mut self, from: NodeId, to: NodeId) -> Result<(), EdgeError> {
+        self.dag.add_edge(from, to)
+    }
+
+    /// Union a state into a node's history, returning whether the node exists
+    ///
+    /// The state is placed by tick, after any states with the same tick.
+    pub fn merge(&mut self, id: NodeId, state: T, at: Tick) -> bool {
+        let Some(history) = self.dag.get_mut(id) else {
+            return false;
+        };
+        let position = history.partition_point(|stamped| stamped.at <= at);
+        history.insert(position, Stamped { at, state });
+        true
+    }
+
+    /// Every state merged into a node, oldest first
+    pub fn history(&self, id: NodeId) -> &[Stamped<T>] {
+        self.dag.get(id).map_or(&[], Vec::as_slice)
+    }
+
+    /// Most recent state of a node
+    pub fn latest(&self, id: NodeId) -> Option<&T> {
+        self.history(id).last().map(|stamped| &stamped.state)
+    }
+
+    /// Replay every state of every node in tick order
+    ///
+    /// States sharing a tick are replayed in dependency order, so a node never
+    /// appears before its parents within the same tick.
+    pub fn replay<F>(&self, mut f: F)
+    where
+        F: FnMut(NodeId, &Stamped<T>),
+    {
+        let mut events: Vec<(Tick, usize, NodeId, &Stamped<T>)> = Vec::new();
+        for (rank, (id, history)) in self.dag.topological_order().enumerate() {
+            events.extend(
+                history
+                    .iter()
+                    .map(|stamped| (stamped.at, rank, id, stamped)),
+            );
+        }
+        events.sort_by_key(|&(at, rank, _, _)| (at, rank));
+
+        for (_, _, id, stamped) in events {
+            f(id, stamped);
+        }
+    }
+
+    /// Underlying DAG of histories
+    pub fn dag(&self) -> &DAG<Vec<Stamped<T>>> {
+        &self.dag
+    }
+
+    /// Unwrap into the underlying DAG of histories
+    pub fn into_dag(self) -> DAG<Vec<Stamped<T>>> {
+        self.dag
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn merge_keeps_every_state_in_tick_order() {
+        let mut dag = LosslessDag::new();
+        let id = dag.add_node("a", 5).unwrap();
+        assert!(dag.merge(id, "b", 2));
+        assert!(dag.merge(id, "c", 5));
+
+        let states: Vec<(Tick, &str)> = dag.history(id).iter().map(|s| (s.at, s.state)).collect();
+        assert_eq!(states, vec![(2, "b"), (5, "a"), (5, "c")]);
+        assert_eq!(dag.latest(id), Some(&"c"));
+    }
+
+    #[test]
+    fn try_from_dag_rejects_lossy_histories() {
+        let mut empty = DAG::new();
+        let id = empty.add_node(Vec::<Stamped<u8>>::new()).unwrap();
+        assert_eq!(
+            LosslessDag::try_from_dag(empty).err(),
+            Some(LosslessError::EmptyHistory(id))
+        );
+
+        let mut unordered = DAG::new();
+        let id = unordered
+            .add_node(vec![
+                Stamped { at: 3, state: 0u8 },
+                Stamped { at: 1, state: 1 },
+            ])
+            .unwrap();
+        assert_eq!(
+            LosslessDag::try_from_dag(unordered).err(),
+            Some(LosslessError::OutOfOrder {
+                node: id,
+                at: 1,
+                after: 3
+            })
+        );
+    }
+
+    #[test]
+    fn replay_orders_by_tick_then_dependency() {
+        let mut dag = LosslessDag::new();
+        let child = dag.add_node("child", 1).unwrap();
+        let parent = dag.add_node("parent", 1).unwrap();
+        dag.add_edge(parent, child).unwrap();
+        dag.merge(child, "early", 0);
+
+        let mut events = Vec::new();
+        dag.replay(|_, stamped| events.push(stamped.state));
+        assert_eq!(events, vec!["early", "parent", "child"]);
+    }
+}
@@ -1,71 +1,1796 @@
 // core/dag-engine/mod.rs
 
-use std::collections::HashMap;
+use std::cmp::Reverse;
+use std::collections::{BTreeMap, BTreeSet, BinaryHeap, HashMap};
+use std::fmt;
+use std::num::NonZeroUsize;
+use std::sync::Arc;
+use std::thread;
+
+pub mod algebra;
+pub mod analysis;
+pub mod closure;
+pub mod components;
+pub mod equivalence;
+#[cfg(feature = "serde")]
+pub mod format;
+pub mod incremental;
+pub mod isomorphic;
+pub mod lossless;
+pub mod persistent;
+pub mod policy;
+pub mod render;
+
+pub use analysis::{DepthReport, NodeDepth};
+pub use closure::Reachability;
+pub use components::{RejoinError, UnionFind};
+#[cfg(feature = "serde")]
+pub use format::{DagDocument, EdgeRecord, FormatError, NodeRecord, FORMAT_VERSION};
+pub use incremental::IncrementalCache;
+pub use isomorphic::{IsomorphicDag, Part, PartitionError, PartitionId};
+pub use lossless::{LosslessDag, LosslessError, Stamped, Tick};
+pub use persistent::{DagDiff, PersistentDag};
+pub use policy::{CustomBound, DepthPolicy, FixedCap, Log2, PolicySpec, ReportOnly};
+pub use render::NodeStyle;
 
 /// Directed Acyclic Graph with O(log n) traversal guarantee
 pub struct DAG<T> {
-    nodes: HashMap<NodeId, Node<T>>,
-    max_depth: usize, // Must be O(log n)
+    slots: Vec<Slot<T>>, // Arena indexed by `NodeId::index`
+    free: Vec<u32>,      // Vacant slot indices, reused last-in first-out
+    len: usize,
+    max_depth: usize,  // Must be O(log n)
+    origin_len: usize, // Most nodes this graph, or the one it was cut from, has held; bounds never shrink below it
+    policy: Arc<dyn DepthPolicy>,
+    reported: Vec<DepthViolation>, // Violations accepted by a non-enforcing policy
+    metadata: BTreeMap<String, String>,
+}
+
+/// Stable node handle: dense arena index plus generation
+///
+/// Ids are allocated in insertion order starting from 0. A vacated slot is
+/// reused with its generation bumped, so a stale id never aliases a new node.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct NodeId {
+    index: u32,
+    generation: u32,
+}
+
+impl NodeId {
+    /// Arena slot of this node
+    pub fn index(self) -> usize {
+        self.index as usize
+    }
+
+    /// Number of times the slot was reused before this id was issued
+    pub fn generation(self) -> u32 {
+        self.generation
+    }
+}
+
+impl fmt::Display for NodeId {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.generation {
+            0 => write!(f, "n{}", self.index),
+            generation => write!(f, "n{}_{}", self.index, generation),
+        }
+    }
+}
+
+/// Arena slot; `node` is `None` while the slot is vacant
+struct Slot<T> {
+    generation: u32,
+    node: Option<Node<T>>,
+}
+
+impl<T> Slot<T> {
+    /// Empty slot standing in for this one in a derived arena
+    ///
+    /// A live slot's generation is bumped so the derived DAG never reissues its id.
+    fn vacated(&self) -> Self {
+        let generation = match self.node {
+            Some(_) => self.generation.wrapping_add(1),
+            None => self.generation,
+        };
+        Slot {
+            generation,
+            node: None,
+        }
+    }
+}
+
+/// Node payload with its outgoing (dependency) edges
+struct Node<T> {
+    data: T,
+    edges: Vec<NodeId>,
+}
+
+/// Depth bound violation
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum DepthViolation {
+    /// `node` sits at `depth`, past the policy `bound` for `node_count` nodes
+    ExceedsLogN {
+        node: NodeId,
+        depth: usize,
+        bound: usize,
+        node_count: usize,
+        /// Longest chain ending at `node`, root first
+        path: Vec<NodeId>,
+    },
+}
+
+/// Auxiliary space bound violation
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum AuxSpaceViolation {
+    /// Pushing `node` would grow the DFS stack to `stack_size` frames, past `bound`
+    StackOverflow {
+        node: NodeId,
+        stack_size: usize,
+        bound: usize,
+        node_count: usize,
+        /// Stack at the moment of overflow plus `node`, root first
+        path: Vec<NodeId>,
+    },
+    /// `measured` bytes were allocated inside `scope`, past the `allowed` budget for `node_count`
+    BudgetExceeded {
+        scope: &'static str,
+        measured: usize,
+        allowed: usize,
+        node_count: usize,
+    },
+}
+
+impl fmt::Display for DepthViolation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            DepthViolation::ExceedsLogN {
+                node,
+                depth,
+                bound,
+                node_count,
+                path,
+            } => write!(
+                f,
+                "node {node} at depth {depth} exceeds depth bound {bound} for {node_count} nodes (path: {})",
+                Path(path)
+            ),
+        }
+    }
+}
+
+impl std::error::Error for DepthViolation {}
+
+impl fmt::Display for AuxSpaceViolation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            AuxSpaceViolation::StackOverflow {
+                node,
+                stack_size,
+                bound,
+                node_count,
+                path,
+            } => write!(
+                f,
+                "traversal stack of {stack_size} frames at node {node} exceeds bound {bound} for {node_count} nodes (path: {})",
+                Path(path)
+            ),
+            AuxSpaceViolation::BudgetExceeded {
+                scope,
+                measured,
+                allowed,
+                node_count,
+            } => write!(
+                f,
+                "{scope} allocated {measured} bytes, past the {allowed}-byte budget for {node_count} nodes"
+            ),
+        }
+    }
+}
+
+impl std::error::Error for AuxSpaceViolation {}
+
+/// Visitor decision for [`DAG::try_traverse`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Visit {
+    /// Descend into this node's children
+    Continue,
+    /// Do not descend from this node; children may still be reached via other parents
+    SkipChildren,
+    /// End the traversal successfully
+    Stop,
+}
+
+/// Per-node result of [`DAG::traverse_parallel`]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum NodeOutcome<R, E> {
+    /// Visitor succeeded
+    Done(R),
+    /// Visitor failed on this node
+    Failed(E),
+    /// Not scheduled because the upstream node `failed` did not succeed
+    Skipped { failed: NodeId },
+}
+
+/// Edge insertion failure
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum EdgeError {
+    /// Endpoint is not part of this DAG
+    UnknownNode(NodeId),
+    /// Edge would close a cycle; `path` runs `from → to → … → from`
+    Cycle { path: Vec<NodeId> },
+    /// Edge would push the graph past its depth bound
+    Depth(DepthViolation),
+}
+
+impl From<DepthViolation> for EdgeError {
+    fn from(violation: DepthViolation) -> Self {
+        EdgeError::Depth(violation)
+    }
+}
+
+impl fmt::Display for EdgeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            EdgeError::UnknownNode(id) => write!(f, "node {id} is not in this DAG"),
+            EdgeError::Cycle { path } => write!(f, "edge would close cycle {}", Path(path)),
+            EdgeError::Depth(violation) => write!(f, "edge rejected: {violation}"),
+        }
+    }
+}
+
+impl std::error::Error for EdgeError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            EdgeError::Depth(violation) => Some(violation),
+            _ => None,
+        }
+    }
+}
+
+/// Renders node ids as `n0 → n1 → n2`
+struct Path<'a>(&'a [NodeId]);
+
+impl fmt::Display for Path<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (position, id) in self.0.iter().enumerate() {
+            if position > 0 {
+                f.write_str(" → ")?;
+            }
+            write!(f, "{id}")?;
+        }
+        Ok(())
+    }
+}
+
+impl<T> Default for DAG<T> {
+    fn default() -> Self {
+        Self::new()
+    }
 }
 
 impl<T> DAG<T> {
-    /// Create new DAG with depth bound
+    /// Create new DAG with the default `Log2` depth bound
     pub fn new() -> Self {
+        Self::with_policy(Log2::default())
+    }
+
+    /// Create new DAG whose depth bound is set by `policy`
+    pub fn with_policy<P: DepthPolicy + 'static>(policy: P) -> Self {
         Self {
-            nodes: HashMap::new(),
+            slots: Vec::new(),
+            free: Vec::new(),
+            len: 0,
             max_depth: 0,
+            origin_len: 0,
+            policy: Arc::new(policy),
+            reported: Vec::new(),
+            metadata: BTreeMap::new(),
         }
     }
-    
+
+    /// Depth policy this DAG was built with
+    pub fn policy(&self) -> &dyn DepthPolicy {
+        &*self.policy
+    }
+
+    /// Violations accepted because the policy does not enforce its bound
+    pub fn reported_violations(&self) -> &[DepthViolation] {
+        &self.reported
+    }
+
+    /// Free-form key/value annotations, kept by `map` and serialized documents
+    pub fn metadata(&self) -> &BTreeMap<String, String> {
+        &self.metadata
+    }
+
+    /// Mutably borrow the annotations
+    pub fn metadata_mut(&mut self) -> &mut BTreeMap<String, String> {
+        &mut self.metadata
+    }
+
     /// Add node with O(log n) depth guarantee
     pub fn add_node(&mut self, data: T) -> Result<NodeId, DepthViolation> {
-        let id = NodeId::new();
+        let id = self.next_id();
         let depth = self.compute_depth(&id);
-        
+
         // Enforce O(log n) depth
-        if depth > (self.nodes.len() as f64).log2().ceil() as usize {
-            return Err(DepthViolation::ExceedsLogN);
-        }
-        
-        self.nodes.insert(id, Node { data, edges: vec![] });
+        self.check_depth(depth, |_| (id, vec![id]))?;
+
+        self.insert(
+            id,
+            Node {
+                data,
+                edges: vec![],
+            },
+        );
         self.max_depth = self.max_depth.max(depth);
         Ok(id)
     }
-    
+
+    /// Add dependency edge `from → to`, rejecting cycles and depth violations
+    pub fn add_edge(&mut self, from: NodeId, to: NodeId) -> Result<(), EdgeError> {
+        self.link_checked(from, to)
+    }
+
+    /// Remove edge `from → to`, returning whether it existed
+    pub fn remove_edge(&mut self, from: NodeId, to: NodeId) -> bool {
+        if !self.unlink(from, to) {
+            return false;
+        }
+
+        self.max_depth = self.longest_chain();
+        true
+    }
+
+    /// Remove a node and its incident edges, returning its data
+    ///
+    /// The slot is recycled under a new generation, so `id` stays invalid.
+    /// The depth bound keeps the node count from before the removal, so
+    /// chains accepted earlier stay within it.
+    pub fn remove_node(&mut self, id: NodeId) -> Option<T> {
+        let node = self
+            .slots
+            .get_mut(id.index())
+            .filter(|slot| slot.generation == id.generation)?
+            .node
+            .take()?;
+
+        let slot = &mut self.slots[id.index()];
+        slot.generation = slot.generation.wrapping_add(1);
+        self.free.push(id.index);
+        self.origin_len = self.bound_len();
+        self.len -= 1;
+
+        for slot in &mut self.slots {
+            if let Some(other) = slot.node.as_mut() {
+                other.edges.retain(|&child| child != id);
+            }
+        }
+
+        self.max_depth = self.longest_chain();
+        Some(node.data)
+    }
+
+    /// Check for a direct edge `from → to`
+    pub fn has_edge(&self, from: NodeId, to: NodeId) -> bool {
+        self.node(from).is_some_and(|node| node.edges.contains(&to))
+    }
+
+    /// True when `id` refers to a live node of this DAG
+    pub fn contains(&self, id: NodeId) -> bool {
+        self.node(id).is_some()
+    }
+
+    /// Borrow node data
+    pub fn get(&self, id: NodeId) -> Option<&T> {
+        self.node(id).map(|node| &node.data)
+    }
+
+    /// Mutably borrow node data
+    pub fn get_mut(&mut self, id: NodeId) -> Option<&mut T> {
+        self.node_mut(id).map(|node| &mut node.data)
+    }
+
+    /// Direct dependents of a node
+    pub fn children(&self, id: NodeId) -> &[NodeId] {
+        self.node(id).map_or(&[], |node| &node.edges)
+    }
+
+    /// Direct dependencies of a node, in id order
+    pub fn parents(&self, id: NodeId) -> Vec<NodeId> {
+        self.ids()
+            .filter(|&parent| self.has_edge(parent, id))
+            .collect()
+    }
+
+    /// Every node with a path to `id`, in id order
+    pub fn ancestors(&self, id: NodeId) -> Vec<NodeId> {
+        let mut parents: Vec<Vec<NodeId>> = vec![Vec::new(); self.slots.len()];
+        for parent in self.ids() {
+            for child in self.children(parent) {
+                parents[child.index()].push(parent);
+            }
+        }
+
+        self.reachable(id, |node| &parents[node.index()])
+    }
+
+    /// Every node reachable from `id`, in id order
+    pub fn descendants(&self, id: NodeId) -> Vec<NodeId> {
+        self.reachable(id, |node| self.children(node))
+    }
+
+    /// Copy out the induced subgraph on `ids`, keeping every `NodeId` unchanged
+    ///
+    /// Unknown ids are ignored. Slots of excluded nodes stay vacant under a new
+    /// generation, so nodes added later never alias ids of the parent graph. The
+    /// depth bound stays the one for this graph's node count, so any part that
+    /// traverses here also traverses on its own.
+    pub fn subgraph<I>(&self, ids: I) -> DAG<T>
+    where
+        I: IntoIterator<Item = NodeId>,
+        T: Clone,
+    {
+        let mut keep = vec![false; self.slots.len()];
+        for id in ids {
+            if self.contains(id) {
+                keep[id.index()] = true;
+            }
+        }
+
+        let slots = self
+            .slots
+            .iter()
+            .enumerate()
+            .map(
+                |(index, slot)| match slot.node.as_ref().filter(|_| keep[index]) {
+                    Some(node) => Slot {
+                        generation: slot.generation,
+                        node: Some(Node {
+                            data: node.data.clone(),
+                            edges: node
+                                .edges
+                                .iter()
+                                .copied()
+                                .filter(|child| keep[child.index()])
+                                .collect(),
+                        }),
+                    },
+                    None => slot.vacated(),
+                },
+            )
+            .collect();
+
+        DAG::from_part(slots, Arc::clone(&self.policy), self.bound_len())
+    }
+
+    /// Live node ids in index order
+    pub fn ids(&self) -> impl Iterator<Item = NodeId> + '_ {
+        self.slots.iter().enumerate().filter_map(|(index, slot)| {
+            slot.node.as_ref().map(|_| NodeId {
+                index: index as u32,
+                generation: slot.generation,
+            })
+        })
+    }
+
+    /// Number of nodes
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    /// True when the DAG has no nodes
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Longest root-to-node path currently in the graph
+    pub fn max_depth(&self) -> usize {
+        self.max_depth
+    }
+
+    /// Iterate nodes in dependency order (Kahn's algorithm), ties broken by `NodeId`
+    pub fn topological_order(&self) -> Topological<'_, T> {
+        let in_degree = self.in_degrees();
+        let ready = self
+            .ids()
+            .filter(|id| in_degree[id.index()] == 0)
+            .map(Reverse)
+            .collect();
+
+        Topological {
+            dag: self,
+            in_degree,
+            ready,
+        }
+    }
+
+    /// Group nodes into waves; every node in a wave depends only on earlier waves
+    pub fn layers(&self) -> Vec<Vec<NodeId>> {
+        let mut in_degree = self.in_degrees();
+        let mut wave: Vec<NodeId> = self.ids().filter(|id| in_degree[id.index()] == 0).collect();
+        let mut layers = Vec::with_capacity(self.max_depth + 1);
+
+        while !wave.is_empty() {
+            wave.sort();
+            let mut next = Vec::new();
+            for &id in &wave {
+                for child in self.children(id) {
+                    let degree = &mut in_degree[child.index()];
+                    *degree -= 1;
+                    if *degree == 0 {
+                        next.push(*child);
+                    }
+                }
+            }
+            layers.push(std::mem::replace(&mut wave, next));
+        }
+
+        layers
+    }
+
+    /// Run `f` on every node, one topological wave at a time across a thread pool
+    ///
+    /// Nodes within a wave are independent and run concurrently. Descendants of a
+    /// failed node are never scheduled and are reported as `Skipped`.
+    pub fn traverse_parallel<F, R, E>(&self, f: F) -> BTreeMap<NodeId, NodeOutcome<R, E>>
+    where
+        T: Sync,
+        F: Fn(&T) -> Result<R, E> + Sync,
+        R: Send,
+        E: Send,
+    {
+        let workers = thread::available_parallelism().map_or(1, NonZeroUsize::get);
+        let mut outcomes = BTreeMap::new();
+        let mut blocked: HashMap<NodeId, NodeId> = HashMap::new();
+
+        for wave in self.layers() {
+            let (runnable, skipped): (Vec<NodeId>, Vec<NodeId>) =
+                wave.into_iter().partition(|id| !blocked.contains_key(id));
+
+            for id in skipped {
+                let failed = blocked[&id];
+                for &child in self.children(id) {
+                    blocked.entry(child).or_insert(failed);
+                }
+                outcomes.insert(id, NodeOutcome::Skipped { failed });
+            }
+
+            if runnable.is_empty() {
+                continue;
+            }
+
+            // One contiguous chunk of the wave per worker
+            let chunk_size = runnable.len().div_ceil(workers);
+            let results: Vec<(NodeId, Result<R, E>)> = thread::scope(|scope| {
+                let handles: Vec<_> = runnable
+                    .chunks(chunk_size)
+                    .map(|chunk| {
+                        let f = &f;
+                        scope.spawn(move || {
+                            chunk
+                                .iter()
+                                .map(|&id| (id, f(&self[id])))
+                                .collect::<Vec<_>>()
+                        })
+                    })
+                    .collect();
+
+                handles
+                    .into_iter()
+                    .flat_map(|handle| handle.join().expect("visitor panicked"))
+                    .collect()
+            });
+
+            for (id, result) in results {
+                let outcome = match result {
+                    Ok(value) => NodeOutcome::Done(value),
+                    Err(error) => {
+                        for &child in self.children(id) {
+                            blocked.entry(child).or_insert(id);
+                        }
+                        NodeOutcome::Failed(error)
+                    }
+                };
+                outcomes.insert(id, outcome);
+            }
+        }
+
+        outcomes
+    }
+
     /// Traverse with O(log n) auxiliary space
-    pub fn traverse<F>(&self, f: F) -> Result<(), AuxSpaceViolation>
+    ///
+    /// Depth-first preorder from each root in id order; every node is visited
+    /// once, on first discovery. Use `topological_order` or
+    /// `try_traverse_topological` for dependency order.
+    pub fn traverse<F>(&self, mut f: F) -> Result<(), AuxSpaceViolation>
     where
         F: FnMut(&T),
     {
-        // Stack size bounded by O(log n)
-        let max_stack_size = (self.nodes.len() as f64).log2().ceil() as usize;
-        let mut stack = Vec::with_capacity(max_stack_size);
-        
-        // Topological traversal
-        for root in self.find_roots() {
-            self.dfs_bounded(root, &mut stack, max_stack_size, &f)?;
-        }
-        
+        self.try_traverse(|_, data| {
+            f(data);
+            Ok::<_, AuxSpaceViolation>(Visit::Continue)
+        })
+    }
+
+    /// Traverse like `traverse` with a fallible visitor that steers the walk
+    ///
+    /// The first visitor error aborts the traversal and is returned as is; a
+    /// stack overflow is converted into `E`.
+    pub fn try_traverse<F, E>(&self, mut f: F) -> Result<(), E>
+    where
+        F: FnMut(NodeId, &T) -> Result<Visit, E>,
+        E: From<AuxSpaceViolation>,
+    {
+        let mut dfs = DfsCursor::new(self);
+        let mut descend = true;
+
+        while let Some(id) = dfs.step(self, descend)? {
+            descend = match f(id, &self[id])? {
+                Visit::Continue => true,
+                Visit::SkipChildren => false,
+                Visit::Stop => break,
+            };
+        }
+
         Ok(())
     }
-    
-    fn dfs_bounded<F>(
-        &self,
-        node_id: NodeId,
-        stack: &mut Vec<NodeId>,
-        max_size: usize,
-        f: &F,
-    ) -> Result<(), AuxSpaceViolation>
+
+    /// Walk in dependency order (`topological_order`) with a fallible visitor
+    ///
+    /// Every parent of a node is visited before it. A node is visited when it
+    /// is a root or some visited parent returned `Visit::Continue`, so
+    /// `SkipChildren` prunes descendants reachable only through skipped nodes.
+    /// The first visitor error aborts the walk and is returned as is.
+    pub fn try_traverse_topological<F, E>(&self, mut f: F) -> Result<(), E>
     where
-        F: FnMut(&T),
+        F: FnMut(NodeId, &T) -> Result<Visit, E>,
+    {
+        let in_degree = self.in_degrees();
+        let mut reached = BitSet::new(self.slots.len());
+        for id in self.ids().filter(|id| in_degree[id.index()] == 0) {
+            reached.insert(id.index());
+        }
+
+        for (id, data) in self.topological_order() {
+            if !reached.contains(id.index()) {
+                continue;
+            }
+            match f(id, data)? {
+                Visit::Continue => {
+                    for child in self.children(id) {
+                        reached.insert(child.index());
+                    }
+                }
+                Visit::SkipChildren => {}
+                Visit::Stop => break,
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Traverse like `traverse`, handing the visitor mutable access to each node
+    pub fn traverse_mut<F>(&mut self, mut f: F) -> Result<(), AuxSpaceViolation>
+    where
+        F: FnMut(&mut T),
+    {
+        let mut dfs = DfsCursor::new(self);
+
+        while let Some(id) = dfs.step(self, true)? {
+            f(&mut self[id]);
+        }
+
+        Ok(())
+    }
+
+    /// Transform every node's data, keeping node ids, edges and the depth policy
+    pub fn map<U, F>(self, mut f: F) -> DAG<U>
+    where
+        F: FnMut(T) -> U,
+    {
+        self.map_nodes(|_, data| f(data))
+    }
+
+    /// `map` with the id of each node passed alongside its data
+    fn map_nodes<U, F>(self, mut f: F) -> DAG<U>
+    where
+        F: FnMut(NodeId, T) -> U,
+    {
+        DAG {
+            slots: self
+                .slots
+                .into_iter()
+                .enumerate()
+                .map(|(index, slot)| Slot {
+                    generation: slot.generation,
+                    node: slot.node.map(|node| Node {
+                        data: f(
+                            NodeId {
+                                index: index as u32,
+                                generation: slot.generation,
+                            },
+                            node.data,
+                        ),
+                        edges: node.edges,
+                    }),
+                })
+                .collect(),
+            free: self.free,
+            len: self.len,
+            max_depth: self.max_depth,
+            origin_len: self.origin_len,
+            policy: self.policy,
+            reported: self.reported,
+            metadata: self.metadata,
+        }
+    }
+
+    /// Assemble a DAG around an existing arena, deriving the bookkeeping
+    fn from_slots(slots: Vec<Slot<T>>, policy: Arc<dyn DepthPolicy>) -> Self {
+        // Lowest vacant index is reused first
+        let free = (0..slots.len() as u32)
+            .rev()
+            .filter(|&index| slots[index as usize].node.is_none())
+            .collect();
+        let mut dag = DAG {
+            len: slots.iter().filter(|slot| slot.node.is_some()).count(),
+            slots,
+            free,
+            max_depth: 0,
+            origin_len: 0,
+            policy,
+            reported: Vec::new(),
+            metadata: BTreeMap::new(),
+        };
+        dag.max_depth = dag.longest_chain();
+        dag
+    }
+
+    /// Move nodes into `count` DAGs by per-slot `labels`, keeping every `NodeId`
+    ///
+    /// Callers guarantee no edge joins two labels.
+    fn split(self, labels: &[usize], count: usize) -> Vec<DAG<T>> {
+        let origin_len = self.bound_len();
+        let generations: Vec<u32> = self
+            .slots
+            .iter()
+            .map(|slot| slot.vacated().generation)
+            .collect();
+        let mut parts: Vec<Vec<Slot<T>>> = (0..count)
+            .map(|_| {
+                generations
+                    .iter()
+                    .map(|&generation| Slot {
+                        generation,
+                        node: None,
+                    })
+                    .collect()
+            })
+            .collect();
+
+        for (index, slot) in self.slots.into_iter().enumerate() {
+            if let Some(node) = slot.node {
+                parts[labels[index]][index] = Slot {
+                    generation: slot.generation,
+                    node: Some(node),
+                };
+            }
+        }
+
+        parts
+            .into_iter()
+            .map(|slots| DAG::from_part(slots, Arc::clone(&self.policy), origin_len))
+            .collect()
+    }
+
+    /// `from_slots` for a part cut from a graph of `origin_len` nodes, keeping its bound
+    fn from_part(slots: Vec<Slot<T>>, policy: Arc<dyn DepthPolicy>, origin_len: usize) -> Self {
+        let mut part = DAG::from_slots(slots, policy);
+        part.origin_len = origin_len;
+        part
+    }
+
+    /// Node count the depth bound is computed for
+    fn bound_len(&self) -> usize {
+        self.len.max(self.origin_len)
+    }
+
+    /// Depth of a node (longest path from any root), 0 if absent
+    fn compute_depth(&self, id: &NodeId) -> usize {
+        if !self.contains(*id) {
+            return 0;
+        }
+        self.depths().0[id.index()]
+    }
+
+    /// Incoming edge count of every node by slot index
+    fn in_degrees(&self) -> Vec<usize> {
+        let mut in_degree = vec![0; self.slots.len()];
+        for id in self.ids() {
+            for child in self.children(id) {
+                in_degree[child.index()] += 1;
+            }
+        }
+        in_degree
+    }
+
+    /// Nodes with no incoming edges, in id order
+    fn find_roots(&self) -> Vec<NodeId> {
+        let in_degree = self.in_degrees();
+        self.ids().filter(|id| in_degree[id.index()] == 0).collect()
+    }
+
+    /// Nodes reachable from `start` through `next`, excluding `start`, in id order
+    fn reachable<'a, N>(&self, start: NodeId, next: N) -> Vec<NodeId>
+    where
+        N: Fn(NodeId) -> &'a [NodeId],
+    {
+        if !self.contains(start) {
+            return Vec::new();
+        }
+
+        let mut seen = vec![false; self.slots.len()];
+        let mut stack = vec![start];
+        let mut found = Vec::new();
+
+        while let Some(id) = stack.pop() {
+            for &other in next(id) {
+                if !seen[other.index()] {
+                    seen[other.index()] = true;
+                    found.push(other);
+                    stack.push(other);
+                }
+            }
+        }
+
+        found.sort();
+        found
+    }
+
+    /// Id the next `insert` will occupy: the most recently vacated slot, else a new one
+    fn next_id(&self) -> NodeId {
+        match self.free.last() {
+            Some(&index) => NodeId {
+                index,
+                generation: self.slots[index as usize].generation,
+            },
+            None => NodeId {
+                index: u32::try_from(self.slots.len()).expect("DAG exceeds u32::MAX slots"),
+                generation: 0,
+            },
+        }
+    }
+
+    /// Occupy the slot reserved by `next_id`
+    fn insert(&mut self, id: NodeId, node: Node<T>) {
+        if self.free.last() == Some(&id.index) {
+            self.free.pop();
+        } else {
+            self.slots.push(Slot {
+                generation: id.generation,
+                node: None,
+            });
+        }
+        self.slots[id.index()].node = Some(node);
+        self.len += 1;
+    }
+
+    fn node(&self, id: NodeId) -> Option<&Node<T>> {
+        self.slots
+            .get(id.index())
+            .filter(|slot| slot.generation == id.generation)
+            .and_then(|slot| slot.node.as_ref())
+    }
+
+    fn node_mut(&mut self, id: NodeId) -> Option<&mut Node<T>> {
+        self.slots
+            .get_mut(id.index())
+            .filter(|slot| slot.generation == id.generation)
+            .and_then(|slot| slot.node.as_mut())
+    }
+}
+
+impl<T> std::ops::Index<NodeId> for DAG<T> {
+    type Output = T;
+
+    fn index(&self, id: NodeId) -> &T {
+        self.get(id)
+            .unwrap_or_else(|| panic!("node {id} is not in this DAG"))
+    }
+}
+
+impl<T> std::ops::IndexMut<NodeId> for DAG<T> {
+    fn index_mut(&mut self, id: NodeId) -> &mut T {
+        self.get_mut(id)
+            .unwrap_or_else(|| panic!("node {id} is not in this DAG"))
+    }
+}
+
+/// Arena shape shared by `DAG` and `PersistentDag`
+///
+/// Both run the same cycle, depth and edge checks through it.
+trait Adjacency {
+    /// Slots, live or vacant; every id indexes below this
+    fn slot_count(&self) -> usize;
+
+    /// Live node ids in index order
+    fn ids(&self) -> impl Iterator<Item = NodeId> + '_;
+
+    /// Direct dependents of a node
+    fn children(&self, id: NodeId) -> &[NodeId];
+
+    /// True when `id` refers to a live node
+    fn contains(&self, id: NodeId) -> bool;
+
+    /// Node count the depth bound is computed for
+    fn bound_len(&self) -> usize;
+
+    fn policy(&self) -> &dyn DepthPolicy;
+
+    /// Keep a violation accepted by a non-enforcing policy
+    fn report(&mut self, violation: DepthViolation);
+
+    /// Append `to` to the edges of `from`, without any check, and record the
+    /// new depths of the nodes it `deepened`, as found by `deepened_by`
+    fn link(&mut self, from: NodeId, to: NodeId, deepened: &[(NodeId, usize, NodeId)]);
+
+    /// Drop the edge `from → to` without touching depth bookkeeping
+    fn unlink(&mut self, from: NodeId, to: NodeId) -> bool;
+
+    /// Depth of each live node, valid until the next update
+    fn depth_of(&self) -> impl Fn(NodeId) -> usize + '_;
+
+    /// Direct dependencies of a node
+    fn parents(&self, id: NodeId) -> Vec<NodeId>;
+
+    /// Add `from → to`, rejecting unknown nodes, cycles and depth violations
+    ///
+    /// Only nodes the edge makes deeper are checked: the first of them past
+    /// the bound, shallowest first, is the one reported.
+    fn link_checked(&mut self, from: NodeId, to: NodeId) -> Result<(), EdgeError> {
+        for id in [from, to] {
+            if !self.contains(id) {
+                return Err(EdgeError::UnknownNode(id));
+            }
+        }
+        if self.children(from).contains(&to) {
+            return Ok(());
+        }
+
+        let bound = self.depth_bound();
+        let (deepened, violation) = {
+            let depth = self.depth_of();
+            // Depth grows along every edge, so a path `to → … → from` stays shallower than `from`
+            let limit = depth(from);
+            if let Some(path) = self.path_between(to, from, |id| depth(id) < limit) {
+                let mut cycle = Vec::with_capacity(path.len() + 1);
+                cycle.push(from);
+                cycle.extend(path);
+                return Err(EdgeError::Cycle { path: cycle });
+            }
+
+            let deepened = self.deepened_by(from, to, &depth);
+            let violation = deepened
+                .iter()
+                .find(|&&(_, d, _)| d > bound)
+                .map(|&(node, d, _)| {
+                    // Back through the new chain to `from`, then up its own longest chain
+                    let via: HashMap<NodeId, NodeId> = deepened
+                        .iter()
+                        .map(|&(id, _, parent)| (id, parent))
+                        .collect();
+                    let mut tail = vec![node];
+                    while let Some(&parent) = via.get(&tail[tail.len() - 1]) {
+                        if parent == from {
+                            break;
+                        }
+                        tail.push(parent);
+                    }
+                    let mut path = self.chain_to(from, &depth);
+                    path.extend(tail.into_iter().rev());
+                    (node, d, path)
+                });
+            (deepened, violation)
+        };
+
+        if let Some((node, depth, path)) = violation {
+            self.check_depth(depth, |_| (node, path))
+                .map_err(EdgeError::Depth)?;
+        }
+
+        self.link(from, to, &deepened);
+        Ok(())
+    }
+
+    /// Nodes the edge `from → to` would make deeper, shallowest first, each
+    /// with its new depth and its parent on the chain through the edge
+    fn deepened_by<D>(&self, from: NodeId, to: NodeId, depth: &D) -> Vec<(NodeId, usize, NodeId)>
+    where
+        D: Fn(NodeId) -> usize,
     {
-        if stack.len() >= max_size {
-            return Err(AuxSpaceViolation::StackOverflow);
+        let mut deepened = Vec::new();
+        if depth(from) < depth(to) {
+            return deepened;
         }
-        
-        stack.push(node_id);
-        // Process node...
-        stack.pop();
+
+        // Visiting by old depth settles every deepened parent before its children
+        let mut found: HashMap<NodeId, (usize, NodeId)> =
+            HashMap::from([(to, (depth(from) + 1, from))]);
+        let mut queue = BTreeSet::from([(depth(to), to)]);
+        while let Some((_, id)) = queue.pop_first() {
+            let (new_depth, parent) = found[&id];
+            deepened.push((id, new_depth, parent));
+            for &child in self.children(id) {
+                let current = found.get(&child).map_or(depth(child), |&(d, _)| d);
+                if new_depth + 1 > current {
+                    found.insert(child, (new_depth + 1, id));
+                    queue.insert((depth(child), child));
+                }
+            }
+        }
+        deepened
+    }
+
+    /// Longest root-to-`id` chain, root first, through smaller ids on ties
+    fn chain_to<D>(&self, id: NodeId, depth: &D) -> Vec<NodeId>
+    where
+        D: Fn(NodeId) -> usize,
+    {
+        let mut chain = vec![id];
+        loop {
+            let last = chain[chain.len() - 1];
+            let parent = self
+                .parents(last)
+                .into_iter()
+                .filter(|&parent| depth(parent) + 1 == depth(last))
+                .min();
+            match parent {
+                Some(parent) => chain.push(parent),
+                None => break,
+            }
+        }
+        chain.reverse();
+        chain
+    }
+
+    /// Depth bound for the current node count, per the policy
+    fn depth_bound(&self) -> usize {
+        self.policy().bound(self.bound_len())
+    }
+
+    /// Reject `depth` if it breaks the bound, or record it under a reporting policy
+    ///
+    /// `locate` names the offending node and its chain; it only runs on violation.
+    fn check_depth<L>(&mut self, depth: usize, locate: L) -> Result<(), DepthViolation>
+    where
+        L: FnOnce(&Self) -> (NodeId, Vec<NodeId>),
+    {
+        let bound = self.depth_bound();
+        if depth <= bound {
+            return Ok(());
+        }
+
+        let (node, path) = locate(self);
+        let violation = DepthViolation::ExceedsLogN {
+            node,
+            depth,
+            bound,
+            node_count: self.bound_len(),
+            path,
+        };
+        if self.policy().enforce() {
+            return Err(violation);
+        }
+
+        self.report(violation);
         Ok(())
     }
+
+    /// Deepest node (smallest id on ties) and the longest chain ending at it
+    fn deepest_chain(&self) -> (NodeId, Vec<NodeId>) {
+        let (depths, deepest_parent) = self.depths();
+        let deepest = self
+            .ids()
+            .max_by_key(|id| (depths[id.index()], Reverse(*id)))
+            .expect("a violating DAG has nodes");
+
+        let mut chain = vec![deepest];
+        while let Some(parent) = deepest_parent[chain[chain.len() - 1].index()] {
+            chain.push(parent);
+        }
+        chain.reverse();
+        (deepest, chain)
+    }
+
+    /// Longest root-to-node path, in edges
+    fn longest_chain(&self) -> usize {
+        self.depths().0.into_iter().max().unwrap_or(0)
+    }
+
+    /// Longest-path depth of every slot, with the parent on that path, in O(V + E)
+    ///
+    /// Ties go to the smaller parent id, matching `DepthReport::chain_to`.
+    fn depths(&self) -> (Vec<usize>, Vec<Option<NodeId>>) {
+        let mut in_degree = vec![0usize; self.slot_count()];
+        for id in self.ids() {
+            for child in self.children(id) {
+                in_degree[child.index()] += 1;
+            }
+        }
+
+        let mut depths = vec![0; self.slot_count()];
+        let mut deepest_parent: Vec<Option<NodeId>> = vec![None; self.slot_count()];
+        let mut ready: Vec<NodeId> = self.ids().filter(|id| in_degree[id.index()] == 0).collect();
+        while let Some(id) = ready.pop() {
+            let depth = depths[id.index()] + 1;
+            for &child in self.children(id) {
+                let slot = child.index();
+                let longer = depth > depths[slot];
+                if longer || (depth == depths[slot] && deepest_parent[slot].is_some_and(|p| id < p))
+                {
+                    depths[slot] = depth;
+                    deepest_parent[slot] = Some(id);
+                }
+
+                in_degree[slot] -= 1;
+                if in_degree[slot] == 0 {
+                    ready.push(child);
+                }
+            }
+        }
+
+        (depths, deepest_parent)
+    }
+
+    /// Some directed path `from → … → to` through nodes `within` accepts, if one exists
+    fn path_between<W>(&self, from: NodeId, to: NodeId, within: W) -> Option<Vec<NodeId>>
+    where
+        W: Fn(NodeId) -> bool,
+    {
+        let mut parent: HashMap<NodeId, NodeId> = HashMap::new();
+        let mut stack = vec![from];
+
+        while let Some(id) = stack.pop() {
+            if id == to {
+                let mut path = vec![to];
+                let mut cursor = to;
+                while let Some(&prev) = parent.get(&cursor) {
+                    path.push(prev);
+                    cursor = prev;
+                }
+                path.reverse();
+                return Some(path);
+            }
+            for &child in self.children(id) {
+                if child != from && !parent.contains_key(&child) && (child == to || within(child)) {
+                    parent.insert(child, id);
+                    stack.push(child);
+                }
+            }
+        }
+
+        None
+    }
+}
+
+impl<T> Adjacency for DAG<T> {
+    fn slot_count(&self) -> usize {
+        self.slots.len()
+    }
+
+    fn depth_of(&self) -> impl Fn(NodeId) -> usize + '_ {
+        let (depths, _) = self.depths();
+        move |id: NodeId| depths[id.index()]
+    }
+
+    fn parents(&self, id: NodeId) -> Vec<NodeId> {
+        DAG::parents(self, id)
+    }
+
+    fn ids(&self) -> impl Iterator<Item = NodeId> + '_ {
+        DAG::ids(self)
+    }
+
+    fn children(&self, id: NodeId) -> &[NodeId] {
+        DAG::children(self, id)
+    }
+
+    fn contains(&self, id: NodeId) -> bool {
+        DAG::contains(self, id)
+    }
+
+    fn bound_len(&self) -> usize {
+        DAG::bound_len(self)
+    }
+
+    fn policy(&self) -> &dyn DepthPolicy {
+        &*self.policy
+    }
+
+    fn report(&mut self, violation: DepthViolation) {
+        self.reported.push(violation);
+    }
+
+    fn link(&mut self, from: NodeId, to: NodeId, deepened: &[(NodeId, usize, NodeId)]) {
+        if let Some(node) = self.node_mut(from) {
+            node.edges.push(to);
+        }
+        // Depths are recomputed on demand; only the maximum is kept
+        if let Some(deepest) = deepened.iter().map(|&(_, depth, _)| depth).max() {
+            self.max_depth = self.max_depth.max(deepest);
+        }
+    }
+
+    fn unlink(&mut self, from: NodeId, to: NodeId) -> bool {
+        let Some(node) = self.node_mut(from) else {
+            return false;
+        };
+        let before = node.edges.len();
+        node.edges.retain(|&child| child != to);
+        node.edges.len() != before
+    }
+}
+
+/// Bounded depth-first walk with an explicit stack
+///
+/// Each frame is `(node, next child index)`, so a parent resumes exactly where
+/// it left off once a child subtree is done. The cursor holds no borrow of the
+/// DAG between steps, which lets callers mutate the node just yielded.
+struct DfsCursor {
+    roots: std::vec::IntoIter<NodeId>,
+    stack: Vec<(NodeId, usize)>,
+    visited: BitSet,
+    max_size: usize,
+    last: Option<NodeId>, // Yielded node, entered on the next step if `descend`
+}
+
+impl DfsCursor {
+    fn new<T>(dag: &DAG<T>) -> Self {
+        // The stack holds one root-to-node path: at most depth bound + 1 frames,
+        // or the deepest accepted path when the policy only reports violations
+        let max_size = if dag.policy.enforce() {
+            dag.depth_bound() + 1
+        } else {
+            dag.max_depth + 1
+        };
+
+        Self {
+            roots: dag.find_roots().into_iter(),
+            stack: Vec::with_capacity(max_size),
+            visited: BitSet::new(dag.slots.len()),
+            max_size,
+            last: None,
+        }
+    }
+
+    /// Next node in preorder; `descend` decides whether the previous node's
+    /// children are explored
+    fn step<T>(
+        &mut self,
+        dag: &DAG<T>,
+        descend: bool,
+    ) -> Result<Option<NodeId>, AuxSpaceViolation> {
+        if let Some(last) = self.last.take() {
+            if descend {
+                self.stack.push((last, 0));
+            }
+        }
+
+        loop {
+            let Some((id, next)) = self.stack.last_mut() else {
+                let Some(root) = self.roots.next() else {
+                    return Ok(None);
+                };
+                self.visited.insert(root.index());
+                self.last = Some(root);
+                return Ok(Some(root));
+            };
+
+            let Some(&child) = dag.children(*id).get(*next) else {
+                self.stack.pop();
+                continue;
+            };
+            *next += 1;
+
+            if !self.visited.insert(child.index()) {
+                continue;
+            }
+            if self.stack.len() >= self.max_size {
+                let mut path: Vec<NodeId> = self.stack.drain(..).map(|(id, _)| id).collect();
+                path.push(child);
+                return Err(AuxSpaceViolation::StackOverflow {
+                    node: child,
+                    stack_size: path.len(),
+                    bound: self.max_size,
+                    node_count: dag.bound_len(),
+                    path,
+                });
+            }
+
+            self.last = Some(child);
+            return Ok(Some(child));
+        }
+    }
+}
+
+/// Fixed-size bit set over slot indices: n bits of visited state
+#[derive(Clone)]
+struct BitSet {
+    words: Vec<u64>,
+}
+
+impl BitSet {
+    fn new(len: usize) -> Self {
+        Self {
+            words: vec![0; len.div_ceil(64)],
+        }
+    }
+
+    /// Set bit `index`, returning whether it was previously clear
+    fn insert(&mut self, index: usize) -> bool {
+        let (word, mask) = (index / 64, 1u64 << (index % 64));
+        let fresh = self.words[word] & mask == 0;
+        self.words[word] |= mask;
+        fresh
+    }
+
+    fn contains(&self, index: usize) -> bool {
+        self.words
+            .get(index / 64)
+            .is_some_and(|word| word & (1u64 << (index % 64)) != 0)
+    }
+
+    /// Set every bit that is set in `other`
+    fn union_with(&mut self, other: &BitSet) {
+        for (word, other) in self.words.iter_mut().zip(&other.words) {
+            *word |= other;
+        }
+    }
+
+    fn count(&self) -> usize {
+        self.words
+            .iter()
+            .map(|word| word.count_ones() as usize)
+            .sum()
+    }
+}
+
+/// Dependency-order iterator returned by [`DAG::topological_order`]
+pub struct Topological<'a, T> {
+    dag: &'a DAG<T>,
+    in_degree: Vec<usize>,
+    ready: BinaryHeap<Reverse<NodeId>>,
+}
+
+impl<'a, T> Iterator for Topological<'a, T> {
+    type Item = (NodeId, &'a T);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let Reverse(id) = self.ready.pop()?;
+        let dag = self.dag;
+
+        for child in dag.children(id) {
+            let degree = &mut self.in_degree[child.index()];
+            *degree -= 1;
+            if *degree == 0 {
+                self.ready.push(Reverse(*child));
+            }
+        }
+
+        Some((id, &dag[id]))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.ready.len(), Some(self.dag.len))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// `count` nodes holding their insertion position
+    fn nodes(dag: &mut DAG<usize>, count: usize) -> Vec<NodeId> {
+        (0..count).map(|i| dag.add_node(i).unwrap()).collect()
+    }
+
+    #[test]
+    fn add_edge_reports_the_cycle_it_would_close() {
+        let mut dag = DAG::with_policy(FixedCap(8));
+        let ids = nodes(&mut dag, 3);
+        dag.add_edge(ids[0], ids[1]).unwrap();
+        dag.add_edge(ids[1], ids[2]).unwrap();
+
+        assert_eq!(
+            dag.add_edge(ids[2], ids[0]),
+            Err(EdgeError::Cycle {
+                path: vec![ids[2], ids[0], ids[1], ids[2]]
+            })
+        );
+        assert_eq!(
+            dag.add_edge(ids[1], ids[1]),
+            Err(EdgeError::Cycle {
+                path: vec![ids[1], ids[1]]
+            })
+        );
+        assert!(!dag.has_edge(ids[2], ids[0]));
+    }
+
+    #[test]
+    fn add_edge_rejects_unknown_nodes_and_accepts_duplicates() {
+        let mut dag = DAG::new();
+        let ids = nodes(&mut dag, 2);
+        let stale = dag.next_id();
+
+        assert_eq!(
+            dag.add_edge(ids[0], stale),
+            Err(EdgeError::UnknownNode(stale))
+        );
+        dag.add_edge(ids[0], ids[1]).unwrap();
+        dag.add_edge(ids[0], ids[1]).unwrap();
+        assert_eq!(dag.children(ids[0]), &[ids[1]]);
+    }
+
+    #[test]
+    fn topological_order_breaks_ties_by_smallest_id() {
+        let mut dag = DAG::with_policy(FixedCap(8));
+        let ids = nodes(&mut dag, 5);
+        // 3 → 0, 4 → 1, 0 → 2, 1 → 2
+        for (from, to) in [(3, 0), (4, 1), (0, 2), (1, 2)] {
+            dag.add_edge(ids[from], ids[to]).unwrap();
+        }
+
+        let order: Vec<usize> = dag.topological_order().map(|(_, &i)| i).collect();
+        assert_eq!(order, vec![3, 0, 4, 1, 2]);
+        assert_eq!(
+            dag.layers(),
+            vec![vec![ids[3], ids[4]], vec![ids[0], ids[1]], vec![ids[2]]]
+        );
+    }
+
+    #[test]
+    fn topological_order_places_every_parent_first() {
+        let mut dag = DAG::with_policy(FixedCap(8));
+        let ids = nodes(&mut dag, 6);
+        for (from, to) in [(5, 0), (2, 0), (4, 2), (1, 3), (4, 3)] {
+            dag.add_edge(ids[from], ids[to]).unwrap();
+        }
+
+        let order: Vec<NodeId> = dag.topological_order().map(|(id, _)| id).collect();
+        assert_eq!(order.len(), dag.len());
+        let position = |id: NodeId| order.iter().position(|&other| other == id).unwrap();
+        for id in dag.ids() {
+            for &child in dag.children(id) {
+                assert!(position(id) < position(child));
+            }
+        }
+    }
+
+    #[test]
+    fn traverse_parallel_skips_descendants_of_failures() {
+        let mut dag = DAG::with_policy(FixedCap(8));
+        let ids = nodes(&mut dag, 6);
+        // 0 → 1 → 2, 0 → 3, 4 → 3, 5 alone
+        for (from, to) in [(0, 1), (1, 2), (0, 3), (4, 3)] {
+            dag.add_edge(ids[from], ids[to]).unwrap();
+        }
+
+        let outcomes = dag.traverse_parallel(|&i| if i == 1 { Err("boom") } else { Ok(i * 10) });
+
+        assert_eq!(outcomes[&ids[0]], NodeOutcome::Done(0));
+        assert_eq!(outcomes[&ids[1]], NodeOutcome::Failed("boom"));
+        assert_eq!(outcomes[&ids[2]], NodeOutcome::Skipped { failed: ids[1] });
+        assert_eq!(outcomes[&ids[3]], NodeOutcome::Done(30));
+        assert_eq!(outcomes[&ids[4]], NodeOutcome::Done(40));
+        assert_eq!(outcomes[&ids[5]], NodeOutcome::Done(50));
+    }
+
+    #[test]
+    fn traverse_parallel_carries_the_original_failure_down_the_chain() {
+        let mut dag = DAG::with_policy(FixedCap(8));
+        let ids = nodes(&mut dag, 4);
+        for (from, to) in [(0, 1), (1, 2), (2, 3)] {
+            dag.add_edge(ids[from], ids[to]).unwrap();
+        }
+
+        let outcomes = dag.traverse_parallel(|&i| if i == 0 { Err(()) } else { Ok(()) });
+
+        assert_eq!(outcomes.len(), 4);
+        for &id in &ids[1..] {
+            assert_eq!(outcomes[&id], NodeOutcome::Skipped { failed: ids[0] });
+        }
+    }
+
+    #[test]
+    fn ids_follow_insertion_order() {
+        let mut dag = DAG::new();
+        let ids = nodes(&mut dag, 3);
+        let names: Vec<String> = ids.iter().map(NodeId::to_string).collect();
+        assert_eq!(names, ["n0", "n1", "n2"]);
+    }
+
+    #[test]
+    fn stale_ids_never_alias_a_reused_slot() {
+        let mut dag = DAG::new();
+        let ids = nodes(&mut dag, 2);
+        assert_eq!(dag.remove_node(ids[0]), Some(0));
+
+        let reused = dag.add_node(7).unwrap();
+        assert_eq!(reused.index(), ids[0].index());
+        assert_eq!(reused.generation(), 1);
+        assert_eq!(reused.to_string(), "n0_1");
+
+        assert!(!dag.contains(ids[0]));
+        assert_eq!(dag.get(ids[0]), None);
+        assert_eq!(dag.remove_node(ids[0]), None);
+        assert_eq!(
+            dag.add_edge(ids[0], ids[1]),
+            Err(EdgeError::UnknownNode(ids[0]))
+        );
+        assert_eq!(dag[reused], 7);
+    }
+
+    /// 16 nodes: a 5-node chain from node 0 plus 11 isolated nodes
+    fn chain_in_sixteen() -> (DAG<usize>, Vec<NodeId>) {
+        let mut dag = DAG::new();
+        let ids = nodes(&mut dag, 16);
+        for pair in ids[..5].windows(2) {
+            dag.add_edge(pair[0], pair[1]).unwrap();
+        }
+        (dag, ids)
+    }
+
+    #[test]
+    fn subgraph_keeps_the_parent_depth_bound() {
+        let (dag, ids) = chain_in_sixteen();
+        assert!(dag.traverse(|_| {}).is_ok());
+
+        let mut downstream = vec![ids[0]];
+        downstream.extend(dag.descendants(ids[0]));
+        let part = dag.subgraph(downstream);
+
+        assert_eq!(part.len(), 5);
+        let mut seen = Vec::new();
+        assert!(part.traverse(|&i| seen.push(i)).is_ok());
+        assert_eq!(seen, vec![0, 1, 2, 3, 4]);
+    }
+
+    #[test]
+    fn subgraph_keeps_ids_and_drops_outside_edges() {
+        let (dag, ids) = chain_in_sixteen();
+        let part = dag.subgraph([ids[1], ids[2], ids[9]]);
+
+        assert_eq!(part.ids().collect::<Vec<_>>(), vec![ids[1], ids[2], ids[9]]);
+        assert_eq!(part.children(ids[1]), &[ids[2]]);
+        assert_eq!(part.children(ids[2]), &[]);
+        assert!(!part.contains(ids[0]));
+    }
+
+    #[test]
+    fn remove_node_drops_incident_edges_and_queries_follow() {
+        let (mut dag, ids) = chain_in_sixteen();
+        assert_eq!(dag.ancestors(ids[3]), vec![ids[0], ids[1], ids[2]]);
+        assert_eq!(dag.descendants(ids[1]), vec![ids[2], ids[3], ids[4]]);
+
+        assert_eq!(dag.remove_node(ids[2]), Some(2));
+        assert_eq!(dag.children(ids[1]), &[]);
+        assert_eq!(dag.ancestors(ids[3]), vec![]);
+        assert_eq!(dag.parents(ids[4]), vec![ids[3]]);
+        assert_eq!(dag.max_depth(), 1);
+    }
+
+    #[test]
+    fn traverse_visits_every_node_once_in_preorder() {
+        let mut dag = DAG::with_policy(FixedCap(8));
+        let ids = nodes(&mut dag, 5);
+        // Diamond 0 → {1, 2} → 3, plus an isolated 4
+        for (from, to) in [(0, 1), (0, 2), (1, 3), (2, 3)] {
+            dag.add_edge(ids[from], ids[to]).unwrap();
+        }
+
+        let mut seen = Vec::new();
+        dag.traverse(|&i| seen.push(i)).unwrap();
+        assert_eq!(seen, vec![0, 1, 3, 2, 4]);
+    }
+
+    #[test]
+    fn removals_keep_the_bound_for_later_edges() {
+        let (mut dag, ids) = chain_in_sixteen();
+        for &id in &ids[5..14] {
+            dag.remove_node(id);
+        }
+
+        assert!(dag.traverse(|_| {}).is_ok());
+        assert_eq!(dag.add_edge(ids[14], ids[15]), Ok(()));
+        assert!(matches!(
+            dag.add_edge(ids[4], ids[15]),
+            Err(EdgeError::Depth(DepthViolation::ExceedsLogN {
+                depth: 5,
+                bound: 4,
+                node_count: 16,
+                ..
+            }))
+        ));
+    }
+
+    #[test]
+    fn traverse_overflows_a_chain_past_the_bound() {
+        let (dag, ids) = chain_in_sixteen();
+        let dag = DAG::from_slots(dag.slots, Arc::new(FixedCap(3)));
+
+        match dag.traverse(|_| {}) {
+            Err(AuxSpaceViolation::StackOverflow {
+                node,
+                stack_size,
+                bound,
+                path,
+                ..
+            }) => {
+                assert_eq!((node, stack_size, bound), (ids[4], 5, 4));
+                assert_eq!(path, ids[..5].to_vec());
+            }
+            other => panic!("expected a stack overflow, got {other:?}"),
+        }
+    }
+
+    #[test]
+    fn report_only_policy_traverses_accepted_chains() {
+        let mut dag = DAG::with_policy(ReportOnly(Log2::default()));
+        let ids = nodes(&mut dag, 6);
+        for pair in ids.windows(2) {
+            dag.add_edge(pair[0], pair[1]).unwrap();
+        }
+        assert_eq!(dag.reported_violations().len(), 2);
+
+        let mut seen = Vec::new();
+        dag.traverse(|&i| seen.push(i)).unwrap();
+        assert_eq!(seen, vec![0, 1, 2, 3, 4, 5]);
+        dag.traverse_mut(|i| *i += 1).unwrap();
+        assert_eq!(dag[ids[5]], 6);
+    }
+
+    #[test]
+    fn report_only_policy_reports_each_node_pushed_past_the_bound() {
+        let mut dag = DAG::with_policy(ReportOnly(Log2::default()));
+        let ids = nodes(&mut dag, 6);
+        for pair in ids.windows(2) {
+            dag.add_edge(pair[0], pair[1]).unwrap();
+        }
+        // Deepens nothing, so the earlier violations are not reported again
+        dag.add_edge(ids[0], ids[2]).unwrap();
+
+        let reported: Vec<_> = dag
+            .reported_violations()
+            .iter()
+            .map(
+                |DepthViolation::ExceedsLogN {
+                     node, depth, path, ..
+                 }| (*node, *depth, path.clone()),
+            )
+            .collect();
+        assert_eq!(
+            reported,
+            vec![(ids[4], 4, ids[..5].to_vec()), (ids[5], 5, ids.clone())]
+        );
+    }
+
+    #[test]
+    fn try_traverse_steers_and_aborts() {
+        let mut dag = DAG::with_policy(FixedCap(8));
+        let ids = nodes(&mut dag, 5);
+        for (from, to) in [(0, 1), (1, 2), (0, 3)] {
+            dag.add_edge(ids[from], ids[to]).unwrap();
+        }
+
+        let mut seen = Vec::new();
+        let skipped = dag.try_traverse(|_, &i| {
+            seen.push(i);
+            Ok::<_, AuxSpaceViolation>(if i == 1 {
+                Visit::SkipChildren
+            } else {
+                Visit::Continue
+            })
+        });
+        assert!(skipped.is_ok());
+        assert_eq!(seen, vec![0, 1, 3, 4]);
+
+        seen.clear();
+        let stopped = dag.try_traverse(|_, &i| {
+            seen.push(i);
+            Ok::<_, AuxSpaceViolation>(if i == 3 { Visit::Stop } else { Visit::Continue })
+        });
+        assert!(stopped.is_ok());
+        assert_eq!(seen, vec![0, 1, 2, 3]);
+
+        #[derive(Debug, PartialEq)]
+        enum Failure {
+            Visitor(NodeId),
+            Space,
+        }
+        impl From<AuxSpaceViolation> for Failure {
+            fn from(_: AuxSpaceViolation) -> Self {
+                Failure::Space
+            }
+        }
+        let failed = dag.try_traverse(|id, &i| match i {
+            2 => Err(Failure::Visitor(id)),
+            _ => Ok(Visit::Continue),
+        });
+        assert_eq!(failed, Err(Failure::Visitor(ids[2])));
+    }
+
+    #[test]
+    fn try_traverse_topological_visits_parents_first() {
+        let mut dag = DAG::with_policy(FixedCap(8));
+        let ids = nodes(&mut dag, 4);
+        // 0 → 2 → 3 and 1 → 3: preorder would reach 3 before 1
+        for (from, to) in [(0, 2), (2, 3), (1, 3)] {
+            dag.add_edge(ids[from], ids[to]).unwrap();
+        }
+
+        let mut seen = Vec::new();
+        dag.try_traverse_topological(|_, &i| {
+            seen.push(i);
+            Ok::<_, ()>(Visit::Continue)
+        })
+        .unwrap();
+        assert_eq!(seen, vec![0, 1, 2, 3]);
+    }
+
+    #[test]
+    fn try_traverse_topological_prunes_only_unreached_descendants() {
+        let mut dag = DAG::with_policy(FixedCap(8));
+        let ids = nodes(&mut dag, 5);
+        // 0 → 1 → 2, 0 → 3, 4 → 3
+        for (from, to) in [(0, 1), (1, 2), (0, 3), (4, 3)] {
+            dag.add_edge(ids[from], ids[to]).unwrap();
+        }
+
+        let mut seen = Vec::new();
+        dag.try_traverse_topological(|_, &i| {
+            seen.push(i);
+            Ok::<_, ()>(if i == 0 {
+                Visit::SkipChildren
+            } else {
+                Visit::Continue
+            })
+        })
+        .unwrap();
+        // 1 and 2 hang only off 0; 3 is still reached through 4
+        assert_eq!(seen, vec![0, 4, 3]);
+
+        let failed = dag.try_traverse_topological(|id, &i| match i {
+            4 => Err(id),
+            _ => Ok(Visit::Continue),
+        });
+        assert_eq!(failed, Err(ids[4]));
+    }
+
+    #[test]
+    fn map_keeps_ids_edges_and_policy() {
+        let mut dag = DAG::with_policy(FixedCap(3));
+        let ids = nodes(&mut dag, 4);
+        dag.add_edge(ids[0], ids[1]).unwrap();
+        dag.add_edge(ids[1], ids[2]).unwrap();
+        dag.remove_node(ids[3]);
+        dag.metadata_mut().insert("owner".into(), "core".into());
+
+        let mut mapped = dag.map(|i| format!("#{i}"));
+        assert_eq!(mapped.ids().collect::<Vec<_>>(), ids[..3].to_vec());
+        assert_eq!(mapped[ids[2]], "#2");
+        assert_eq!(mapped.children(ids[1]), &[ids[2]]);
+        assert_eq!(mapped.max_depth(), 2);
+        assert_eq!(mapped.metadata()["owner"], "core");
+
+        let reused = mapped.add_node("new".into()).unwrap();
+        assert_eq!(reused.index(), ids[3].index());
+        assert!(mapped.add_edge(ids[2], reused).is_ok());
+        let extra = mapped.add_node("x".into()).unwrap();
+        assert!(matches!(
+            mapped.add_edge(reused, extra),
+            Err(EdgeError::Depth(_))
+        ));
+    }
 }
@@ -0,0 +1,904 @@
+// core/dag-engine/persistent/mod.rs
+
+use std::collections::BTreeSet;
+use std::sync::Arc;
+
+use super::{Adjacency, DepthPolicy, DepthViolation, EdgeError, Log2, Node, NodeId, Slot, DAG};
+
+/// Immutable-history DAG: every update copies only the O(log n) trie path it touches
+///
+/// `snapshot` is O(1) and later updates never affect it, so old versions stay
+/// queryable and can be restored by assignment. Slots are never reused: an id
+/// names the same node in every snapshot it appears in.
+///
+/// Each node stores its parents and depth, so edge and node updates only
+/// revisit the nodes whose depth they change.
+pub struct PersistentDag<T> {
+    slots: PersistentVec<Entry<T>>,
+    len: usize,
+    origin_len: usize,       // Most nodes held; the bound never shrinks below it
+    levels: Arc<Vec<usize>>, // Live nodes at each depth, without trailing zeros
+    policy: Arc<dyn DepthPolicy>,
+    reported: Arc<Vec<DepthViolation>>, // Violations accepted by a non-enforcing policy
+}
+
+/// Node and edge changes between two snapshots, each list in id order
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct DagDiff {
+    pub added_nodes: Vec<NodeId>,
+    pub removed_nodes: Vec<NodeId>,
+    /// Present in both with its data replaced
+    pub updated_nodes: Vec<NodeId>,
+    pub added_edges: Vec<(NodeId, NodeId)>,
+    pub removed_edges: Vec<(NodeId, NodeId)>,
+}
+
+impl DagDiff {
+    /// True when both snapshots hold the same nodes, data and edges
+    pub fn is_empty(&self) -> bool {
+        self.added_nodes.is_empty()
+            && self.removed_nodes.is_empty()
+            && self.updated_nodes.is_empty()
+            && self.added_edges.is_empty()
+            && self.removed_edges.is_empty()
+    }
+}
+
+/// Slot of the persistent arena
+struct Entry<T> {
+    generation: u32,
+    node: Option<Stored<T>>,
+}
+
+/// Live node; data sits behind an `Arc` so copies are shallow
+struct Stored<T> {
+    data: Arc<T>,
+    edges: Vec<NodeId>,
+    parents: Vec<NodeId>, // Sources of incoming edges
+    depth: usize,         // Longest path from any root
+}
+
+impl<T> Clone for Stored<T> {
+    fn clone(&self) -> Self {
+        Self {
+            data: Arc::clone(&self.data),
+            edges: self.edges.clone(),
+            parents: self.parents.clone(),
+            depth: self.depth,
+        }
+    }
+}
+
+impl<T> Clone for Entry<T> {
+    fn clone(&self) -> Self {
+        Self {
+            generation: self.generation,
+            node: self.node.clone(),
+        }
+    }
+}
+
+impl<T> Clone for PersistentDag<T> {
+    fn clone(&self) -> Self {
+        self.snapshot()
+    }
+}
+
+impl<T> Default for PersistentDag<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T> PersistentDag<T> {
+    /// Create new persistent DAG with the default `Log2` depth bound
+    pub fn new() -> Self {
+        Self::with_policy(Log2::default())
+    }
+
+    /// Create new persistent DAG whose depth bound is set by `policy`
+    pub fn with_policy<P: DepthPolicy + 'static>(policy: P) -> Self {
+        Self {
+            slots: PersistentVec::new(),
+            len: 0,
+            origin_len: 0,
+            levels: Arc::new(Vec::new()),
+            policy: Arc::new(policy),
+            reported: Arc::new(Vec::new()),
+        }
+    }
+
+    /// Cheap immutable copy of the current version
+    pub fn snapshot(&self) -> Self {
+        Self {
+            slots: self.slots.clone(),
+            len: self.len,
+            origin_len: self.origin_len,
+            levels: Arc::clone(&self.levels),
+            policy: Arc::clone(&self.policy),
+            reported: Arc::clone(&self.reported),
+        }
+    }
+
+    /// Changes that turn snapshot `a` into snapshot `b`
+    ///
+    /// Subtrees shared by both snapshots are skipped, so diffing two nearby
+    /// versions costs O(changes · log n) rather than O(n). A node counts as
+    /// updated when its data compares unequal, so versions built separately
+    /// diff by value.
+    pub fn diff(a: &Self, b: &Self) -> DagDiff
+    where
+        T: PartialEq,
+    {
+        let mut diff = DagDiff::default();
+
+        a.slots.diff(&b.slots, |index, before, after| {
+            let id = |entry: &Entry<T>| NodeId {
+                index: index as u32,
+                generation: entry.generation,
+            };
+            let before = before.and_then(|entry| entry.node.as_ref().map(|node| (id(entry), node)));
+            let after = after.and_then(|entry| entry.node.as_ref().map(|node| (id(entry), node)));
+
+            match (before, after) {
+                (Some((old, old_node)), Some((new, new_node))) if old == new => {
+                    if !Arc::ptr_eq(&old_node.data, &new_node.data)
+                        && old_node.data != new_node.data
+                    {
+                        diff.updated_nodes.push(new);
+                    }
+                    let (old_set, new_set): (BTreeSet<_>, BTreeSet<_>) = (
+                        old_node.edges.iter().collect(),
+                        new_node.edges.iter().collect(),
+                    );
+                    diff.removed_edges
+                        .extend(old_set.difference(&new_set).map(|&&to| (old, to)));
+                    diff.added_edges
+                        .extend(new_set.difference(&old_set).map(|&&to| (new, to)));
+                }
+                (before, after) => {
+                    if let Some((old, node)) = before {
+                        diff.removed_nodes.push(old);
+                        diff.removed_edges
+                            .extend(node.edges.iter().map(|&to| (old, to)));
+                    }
+                    if let Some((new, node)) = after {
+                        diff.added_nodes.push(new);
+                        diff.added_edges
+                            .extend(node.edges.iter().map(|&to| (new, to)));
+                    }
+                }
+            }
+        });
+
+        // A taller trie reports its extra subtrees before the shared prefix
+        diff.added_nodes.sort();
+        diff.removed_nodes.sort();
+        diff.added_edges.sort();
+        diff.removed_edges.sort();
+        diff
+    }
+
+    /// Add node with O(log n) depth guarantee
+    pub fn add_node(&mut self, data: T) -> Result<NodeId, DepthViolation> {
+        let id = NodeId {
+            index: u32::try_from(self.slots.len()).expect("DAG exceeds u32::MAX slots"),
+            generation: 0,
+        };
+
+        // Enforce O(log n) depth
+        self.check_depth(0, |_| (id, vec![id]))?;
+
+        self.slots.push(Entry {
+            generation: 0,
+            node: Some(Stored {
+                data: Arc::new(data),
+                edges: Vec::new(),
+                parents: Vec::new(),
+                depth: 0,
+            }),
+        });
+        self.move_level(None, Some(0));
+        self.len += 1;
+        Ok(id)
+    }
+
+    /// Add dependency edge `from → to`, rejecting cycles and depth violations
+    ///
+    /// Only nodes shallower than `from` are searched for a cycle, and only the
+    /// nodes the edge deepens are rechecked and path-copied.
+    pub fn add_edge(&mut self, from: NodeId, to: NodeId) -> Result<(), EdgeError> {
+        self.link_checked(from, to)
+    }
+
+    /// Remove edge `from → to`, returning whether it existed
+    ///
+    /// Only `to` and the descendants whose depth drops are revisited.
+    pub fn remove_edge(&mut self, from: NodeId, to: NodeId) -> bool {
+        if !self.unlink(from, to) {
+            return false;
+        }
+
+        self.settle([to]);
+        true
+    }
+
+    /// Remove a node and its incident edges, returning its data
+    ///
+    /// The slot stays vacant under a new generation, so `id` stays invalid.
+    /// The depth bound keeps the node count from before the removal, as on
+    /// [`DAG::remove_node`].
+    pub fn remove_node(&mut self, id: NodeId) -> Option<Arc<T>> {
+        let removed = self.node(id)?.clone();
+
+        for &parent in &removed.parents {
+            self.update_node(parent, |node| node.edges.retain(|&child| child != id));
+        }
+        for &child in &removed.edges {
+            self.update_node(child, |node| node.parents.retain(|&parent| parent != id));
+        }
+        self.slots.set(
+            id.index(),
+            Entry {
+                generation: id.generation.wrapping_add(1),
+                node: None,
+            },
+        );
+        self.move_level(Some(removed.depth), None);
+        self.origin_len = self.bound_len();
+        self.len -= 1;
+
+        self.settle(removed.edges);
+        Some(removed.data)
+    }
+
+    /// Replace a node's data, returning the previous data
+    pub fn replace(&mut self, id: NodeId, data: T) -> Option<Arc<T>> {
+        let previous = Arc::clone(&self.node(id)?.data);
+        self.update_node(id, |node| node.data = Arc::new(data));
+        Some(previous)
+    }
+
+    /// Check for a direct edge `from → to`
+    pub fn has_edge(&self, from: NodeId, to: NodeId) -> bool {
+        self.children(from).contains(&to)
+    }
+
+    /// True when `id` refers to a live node of this version
+    pub fn contains(&self, id: NodeId) -> bool {
+        self.entry(id).is_some_and(|entry| entry.node.is_some())
+    }
+
+    /// Borrow node data
+    pub fn get(&self, id: NodeId) -> Option<&T> {
+        self.node(id).map(|node| &*node.data)
+    }
+
+    /// Direct dependents of a node
+    pub fn children(&self, id: NodeId) -> &[NodeId] {
+        self.node(id).map_or(&[], |node| &node.edges)
+    }
+
+    /// Live node ids in index order
+    pub fn ids(&self) -> impl Iterator<Item = NodeId> + '_ {
+        self.slots.iter().enumerate().filter_map(|(index, entry)| {
+            entry.node.as_ref().map(|_| NodeId {
+                index: index as u32,
+                generation: entry.generation,
+            })
+        })
+    }
+
+    /// Number of nodes
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    /// True when the DAG has no nodes
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Longest root-to-node path currently in the graph
+    pub fn max_depth(&self) -> usize {
+        self.levels.len().saturating_sub(1)
+    }
+
+    /// Violations accepted because the policy does not enforce its bound
+    pub fn reported_violations(&self) -> &[DepthViolation] {
+        &self.reported
+    }
+
+    /// Materialize this version as a regular `DAG`, keeping every `NodeId`
+    ///
+    /// Use it to run traversals and analyses against an old snapshot.
+    pub fn to_dag(&self) -> DAG<T>
+    where
+        T: Clone,
+    {
+        let slots = self
+            .slots
+            .iter()
+            .map(|entry| Slot {
+                generation: entry.generation,
+                node: entry.node.as_ref().map(|node| Node {
+                    data: T::clone(&node.data),
+                    edges: node.edges.clone(),
+                }),
+            })
+            .collect();
+
+        DAG::from_part(slots, Arc::clone(&self.policy), self.origin_len)
+    }
+
+    /// Borrow the live or vacant slot `id` points at, if its generation matches
+    fn entry(&self, id: NodeId) -> Option<&Entry<T>> {
+        self.slots
+            .get(id.index())
+            .filter(|entry| entry.generation == id.generation)
+    }
+
+    /// Borrow the live node `id` points at
+    fn node(&self, id: NodeId) -> Option<&Stored<T>> {
+        self.entry(id)?.node.as_ref()
+    }
+
+    /// Path-copy the slot of `id` with its node changed by `change`
+    fn update_node<C>(&mut self, id: NodeId, change: C)
+    where
+        C: FnOnce(&mut Stored<T>),
+    {
+        let Some(mut entry) = self.entry(id).cloned() else {
+            return;
+        };
+        if let Some(node) = entry.node.as_mut() {
+            change(node);
+        }
+        self.slots.set(id.index(), entry);
+    }
+
+    /// Move one node's count from level `from` to level `to`
+    fn move_level(&mut self, from: Option<usize>, to: Option<usize>) {
+        let levels = Arc::make_mut(&mut self.levels);
+        if let Some(depth) = from {
+            levels[depth] -= 1;
+        }
+        if let Some(depth) = to {
+            if levels.len() <= depth {
+                levels.resize(depth + 1, 0);
+            }
+            levels[depth] += 1;
+        }
+        while levels.last() == Some(&0) {
+            levels.pop();
+        }
+    }
+
+    /// Recompute the depth of `starts`, then of each child whose parent changed
+    ///
+    /// Nodes are settled in order of their old depth, which puts every
+    /// changed parent before its children.
+    fn settle<I>(&mut self, starts: I)
+    where
+        I: IntoIterator<Item = NodeId>,
+    {
+        let mut queue: BTreeSet<(usize, NodeId)> = starts
+            .into_iter()
+            .filter_map(|id| Some((self.node(id)?.depth, id)))
+            .collect();
+
+        while let Some((old, id)) = queue.pop_first() {
+            let Some(node) = self.node(id) else {
+                continue;
+            };
+            let depth = node
+                .parents
+                .iter()
+                .filter_map(|&parent| self.node(parent))
+                .map(|parent| parent.depth + 1)
+                .max()
+                .unwrap_or(0);
+            if depth == old {
+                continue;
+            }
+
+            let children = node.edges.clone();
+            self.update_node(id, |node| node.depth = depth);
+            self.move_level(Some(old), Some(depth));
+            queue.extend(
+                children
+                    .into_iter()
+                    .filter_map(|child| Some((self.node(child)?.depth, child))),
+            );
+        }
+    }
+}
+
+impl<T> Adjacency for PersistentDag<T> {
+    fn slot_count(&self) -> usize {
+        self.slots.len()
+    }
+
+    fn ids(&self) -> impl Iterator<Item = NodeId> + '_ {
+        PersistentDag::ids(self)
+    }
+
+    fn children(&self, id: NodeId) -> &[NodeId] {
+        PersistentDag::children(self, id)
+    }
+
+    fn depth_of(&self) -> impl Fn(NodeId) -> usize + '_ {
+        move |id: NodeId| self.node(id).map_or(0, |node| node.depth)
+    }
+
+    fn parents(&self, id: NodeId) -> Vec<NodeId> {
+        self.node(id)
+            .map_or_else(Vec::new, |node| node.parents.clone())
+    }
+
+    fn contains(&self, id: NodeId) -> bool {
+        PersistentDag::contains(self, id)
+    }
+
+    fn bound_len(&self) -> usize {
+        self.len.max(self.origin_len)
+    }
+
+    fn policy(&self) -> &dyn DepthPolicy {
+        &*self.policy
+    }
+
+    fn report(&mut self, violation: DepthViolation) {
+        Arc::make_mut(&mut self.reported).push(violation);
+    }
+
+    fn link(&mut self, from: NodeId, to: NodeId, deepened: &[(NodeId, usize, NodeId)]) {
+        self.update_node(from, |node| node.edges.push(to));
+        self.update_node(to, |node| node.parents.push(from));
+        for &(id, depth, _) in deepened {
+            let Some(old) = self.node(id).map(|node| node.depth) else {
+                continue;
+            };
+            self.update_node(id, |node| node.depth = depth);
+            self.move_level(Some(old), Some(depth));
+        }
+    }
+
+    fn unlink(&mut self, from: NodeId, to: NodeId) -> bool {
+        if !self.has_edge(from, to) {
+            return false;
+        }
+        self.update_node(from, |node| node.edges.retain(|&child| child != to));
+        self.update_node(to, |node| node.parents.retain(|&parent| parent != from));
+        true
+    }
+}
+
+impl<T> From<DAG<T>> for PersistentDag<T> {
+    /// Move a DAG into persistent form, keeping every `NodeId`
+    fn from(dag: DAG<T>) -> Self {
+        let (depths, _) = dag.depths();
+        let mut parents = vec![Vec::new(); dag.slots.len()];
+        for id in dag.ids() {
+            for &child in dag.children(id) {
+                parents[child.index()].push(id);
+            }
+        }
+
+        let mut levels = Vec::new();
+        for id in dag.ids() {
+            let depth = depths[id.index()];
+            if levels.len() <= depth {
+                levels.resize(depth + 1, 0);
+            }
+            levels[depth] += 1;
+        }
+
+        let origin_len = dag.bound_len();
+        let mut slots = PersistentVec::new();
+        for ((slot, parents), depth) in dag.slots.into_iter().zip(parents).zip(depths) {
+            slots.push(Entry {
+                generation: slot.generation,
+                node: slot.node.map(|node| Stored {
+                    data: Arc::new(node.data),
+                    edges: node.edges,
+                    parents,
+                    depth,
+                }),
+            });
+        }
+
+        Self {
+            slots,
+            len: dag.len,
+            origin_len,
+            levels: Arc::new(levels),
+            policy: dag.policy,
+            reported: Arc::new(dag.reported),
+        }
+    }
+}
+
+impl<T> std::ops::Index<NodeId> for PersistentDag<T> {
+    type Output = T;
+
+    fn index(&self, id: NodeId) -> &T {
+        self.get(id)
+            .unwrap_or_else(|| panic!("node {id} is not in this DAG"))
+    }
+}
+
+const BITS: u32 = 5;
+const WIDTH: usize = 1 << BITS;
+const MASK: usize = WIDTH - 1;
+
+/// Persistent vector: a 32-way trie of `Arc` nodes
+///
+/// Clones share the whole trie; `set` and `push` copy only the root-to-leaf
+/// path they change (in place when nothing else shares it).
+struct PersistentVec<A> {
+    root: Arc<Trie<A>>,
+    len: usize,
+    shift: u32, // Index bits consumed above the leaves
+}
+
+#[derive(Clone)]
+enum Trie<A> {
+    Branch(Vec<Arc<Trie<A>>>),
+    Leaf(Vec<A>),
+}
+
+impl<A> Clone for PersistentVec<A> {
+    fn clone(&self) -> Self {
+        Self {
+            root: Arc::clone(&self.root),
+            len: self.len,
+            shift: self.shift,
+        }
+    }
+}
+
+impl<A> Trie<A> {
+    fn children(&self) -> &[Arc<Trie<A>>] {
+        match self {
+            Trie::Branch(children) => children,
+            Trie::Leaf(_) => &[],
+        }
+    }
+
+    fn items(&self) -> &[A] {
+        match self {
+            Trie::Branch(_) => &[],
+            Trie::Leaf(items) => items,
+        }
+    }
+}
+
+impl<A: Clone> PersistentVec<A> {
+    fn new() -> Self {
+        Self {
+            root: Arc::new(Trie::Leaf(Vec::new())),
+            len: 0,
+            shift: 0,
+        }
+    }
+
+    fn len(&self) -> usize {
+        self.len
+    }
+
+    fn get(&self, index: usize) -> Option<&A> {
+        if index >= self.len {
+            return None;
+        }
+
+        let mut node = &*self.root;
+        let mut level = self.shift;
+        loop {
+            match node {
+                Trie::Branch(children) => {
+                    node = &children[(index >> level) & MASK];
+                    level -= BITS;
+                }
+                Trie::Leaf(items) => return items.get(index & MASK),
+            }
+        }
+    }
+
+    fn set(&mut self, index: usize, value: A) {
+        assert!(index < self.len, "index {index} out of range");
+
+        let mut node = &mut self.root;
+        let mut level = self.shift;
+        loop {
+            match Arc::make_mut(node) {
+                Trie::Branch(children) => {
+                    node = &mut children[(index >> level) & MASK];
+                    level -= BITS;
+                }
+                Trie::Leaf(items) => {
+                    items[index & MASK] = value;
+                    return;
+                }
+            }
+        }
+    }
+
+    fn push(&mut self, value: A) {
+        // Full trie: grow a level with the old root as first child
+        if self.len == 1 << (self.shift + BITS) {
+            let old = std::mem::replace(&mut self.root, Arc::new(Trie::Branch(Vec::new())));
+            self.root = Arc::new(Trie::Branch(vec![old]));
+            self.shift += BITS;
+        }
+
+        let index = self.len;
+        let mut node = &mut self.root;
+        let mut level = self.shift;
+        loop {
+            match Arc::make_mut(node) {
+                Trie::Branch(children) => {
+                    let slot = (index >> level) & MASK;
+                    if slot == children.len() {
+                        children.push(Arc::new(match level {
+                            BITS => Trie::Leaf(Vec::with_capacity(WIDTH)),
+                            _ => Trie::Branch(Vec::with_capacity(WIDTH)),
+                        }));
+                    }
+                    node = &mut children[slot];
+                    level -= BITS;
+                }
+                Trie::Leaf(items) => {
+                    items.push(value);
+                    break;
+                }
+            }
+        }
+        self.len += 1;
+    }
+
+    fn iter(&self) -> impl Iterator<Item = &A> + '_ {
+        (0..self.len).map(|index| self.get(index).expect("index below len"))
+    }
+
+    /// Call `f(index, self[index], other[index])` for every index that may
+    /// differ, skipping subtrees the two vectors share
+    fn diff<F>(&self, other: &Self, mut f: F)
+    where
+        F: FnMut(usize, Option<&A>, Option<&A>),
+    {
+        // Align heights: a taller trie's first child covers the whole shorter one
+        let (mut left, mut left_shift) = (&self.root, self.shift);
+        let (mut right, mut right_shift) = (&other.root, other.shift);
+        while left_shift > right_shift {
+            for (position, child) in left.children().iter().enumerate().skip(1) {
+                walk(
+                    Some(child),
+                    None,
+                    left_shift - BITS,
+                    position << left_shift,
+                    &mut f,
+                );
+            }
+            left = &left.children()[0];
+            left_shift -= BITS;
+        }
+        while right_shift > left_shift {
+            for (position, child) in right.children().iter().enumerate().skip(1) {
+                walk(
+                    None,
+                    Some(child),
+                    right_shift - BITS,
+                    position << right_shift,
+                    &mut f,
+                );
+            }
+            right = &right.children()[0];
+            right_shift -= BITS;
+        }
+
+        walk(Some(left), Some(right), left_shift, 0, &mut f);
+    }
+}
+
+/// Recursive step of `PersistentVec::diff` over two subtrees at `level`
+fn walk<A, F>(
+    left: Option<&Arc<Trie<A>>>,
+    right: Option<&Arc<Trie<A>>>,
+    level: u32,
+    base: usize,
+    f: &mut F,
+) where
+    F: FnMut(usize, Option<&A>, Option<&A>),
+{
+    if let (Some(left), Some(right)) = (left, right) {
+        if Arc::ptr_eq(left, right) {
+            return;
+        }
+    }
+
+    if level == 0 {
+        let (left, right) = (
+            left.map_or(&[][..], |node| node.items()),
+            right.map_or(&[][..], |node| node.items()),
+        );
+        for position in 0..left.len().max(right.len()) {
+            f(base + position, left.get(position), right.get(position));
+        }
+        return;
+    }
+
+    let (left, right) = (
+        left.map_or(&[][..], |node| node.children()),
+        right.map_or(&[][..], |node| node.children()),
+    );
+    for position in 0..left.len().max(right.len()) {
+        walk(
+            left.get(position),
+            right.get(position),
+            level - BITS,
+            base + (position << level),
+            f,
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{FixedCap, ReportOnly};
+
+    fn chain(count: usize) -> (PersistentDag<usize>, Vec<NodeId>) {
+        let mut dag = PersistentDag::with_policy(FixedCap(64));
+        let ids: Vec<NodeId> = (0..count).map(|i| dag.add_node(i).unwrap()).collect();
+        for pair in ids.windows(2) {
+            dag.add_edge(pair[0], pair[1]).unwrap();
+        }
+        (dag, ids)
+    }
+
+    #[test]
+    fn snapshots_are_unaffected_by_later_updates() {
+        let (mut dag, ids) = chain(3);
+        let before = dag.snapshot();
+
+        dag.replace(ids[0], 10);
+        dag.remove_edge(ids[1], ids[2]);
+        dag.remove_node(ids[2]);
+
+        assert_eq!(before[ids[0]], 0);
+        assert!(before.has_edge(ids[1], ids[2]));
+        assert_eq!((before.len(), before.max_depth()), (3, 2));
+        assert_eq!((dag.len(), dag.max_depth()), (2, 1));
+        assert!(!dag.contains(ids[2]));
+    }
+
+    #[test]
+    fn diff_lists_every_change() {
+        let (mut dag, ids) = chain(3);
+        let before = dag.snapshot();
+
+        dag.replace(ids[1], 11);
+        dag.remove_node(ids[2]);
+        let added = dag.add_node(3).unwrap();
+        dag.add_edge(ids[0], added).unwrap();
+
+        let diff = PersistentDag::diff(&before, &dag);
+        assert_eq!(
+            diff,
+            DagDiff {
+                added_nodes: vec![added],
+                removed_nodes: vec![ids[2]],
+                updated_nodes: vec![ids[1]],
+                added_edges: vec![(ids[0], added)],
+                removed_edges: vec![(ids[1], ids[2])],
+            }
+        );
+        assert!(PersistentDag::diff(&dag, &dag.snapshot()).is_empty());
+    }
+
+    #[test]
+    fn diff_spans_trie_levels() {
+        let (small, _) = chain(3);
+        let mut large = small.snapshot();
+        let added: Vec<NodeId> = (3..40).map(|i| large.add_node(i).unwrap()).collect();
+
+        let diff = PersistentDag::diff(&small, &large);
+        assert_eq!(diff.added_nodes, added);
+        assert!(diff.removed_nodes.is_empty() && diff.added_edges.is_empty());
+
+        let reverse = PersistentDag::diff(&large, &small);
+        assert_eq!(reverse.removed_nodes, added);
+    }
+
+    #[test]
+    fn edges_share_the_dag_cycle_and_depth_checks() {
+        let mut dag = PersistentDag::with_policy(FixedCap(1));
+        let ids: Vec<NodeId> = (0..3).map(|i| dag.add_node(i).unwrap()).collect();
+        dag.add_edge(ids[0], ids[1]).unwrap();
+
+        assert_eq!(
+            dag.add_edge(ids[1], ids[0]),
+            Err(EdgeError::Cycle {
+                path: vec![ids[1], ids[0], ids[1]]
+            })
+        );
+        assert!(matches!(
+            dag.add_edge(ids[1], ids[2]),
+            Err(EdgeError::Depth(DepthViolation::ExceedsLogN {
+                depth: 2,
+                ..
+            }))
+        ));
+        assert!(!dag.has_edge(ids[1], ids[2]));
+        assert_eq!(dag.max_depth(), 1);
+    }
+
+    #[test]
+    fn reporting_policy_records_and_snapshots_keep_their_reports() {
+        let mut dag = PersistentDag::with_policy(ReportOnly(FixedCap(1)));
+        let ids: Vec<NodeId> = (0..3).map(|i| dag.add_node(i).unwrap()).collect();
+        dag.add_edge(ids[0], ids[1]).unwrap();
+        let before = dag.snapshot();
+        dag.add_edge(ids[1], ids[2]).unwrap();
+
+        assert!(before.reported_violations().is_empty());
+        match dag.reported_violations() {
+            [DepthViolation::ExceedsLogN { node, path, .. }] => {
+                assert_eq!(*node, ids[2]);
+                assert_eq!(*path, ids);
+            }
+            other => panic!("expected one report, got {other:?}"),
+        }
+    }
+
+    #[test]
+    fn dag_round_trip_keeps_ids() {
+        let (dag, ids) = chain(4);
+        let plain = dag.to_dag();
+        assert_eq!(plain.ids().collect::<Vec<_>>(), ids);
+        assert_eq!(plain.max_depth(), 3);
+
+        let back = PersistentDag::from(plain);
+        let diff = PersistentDag::diff(&dag, &back);
+        assert!(diff.updated_nodes.is_empty());
+        assert!(diff.is_empty());
+        assert_eq!(back[ids[3]], 3);
+        assert_eq!(back.max_depth(), 3);
+    }
+
+    /// Stored depth of every node, checked against a full recomputation
+    fn depths_of(dag: &PersistentDag<usize>) -> Vec<(NodeId, usize)> {
+        let report = dag.to_dag().depth_report();
+        let stored: Vec<(NodeId, usize)> = dag.ids().map(|id| (id, dag.depth_of()(id))).collect();
+        let full: Vec<(NodeId, usize)> = report
+            .nodes
+            .iter()
+            .map(|(&id, node)| (id, node.depth))
+            .collect();
+        assert_eq!(stored, full);
+        assert_eq!(dag.max_depth(), report.max_depth);
+        stored
+    }
+
+    #[test]
+    fn removals_lower_depths_incrementally() {
+        let (mut dag, ids) = chain(4);
+        dag.add_edge(ids[0], ids[3]).unwrap();
+        let before = dag.snapshot();
+
+        assert!(dag.remove_edge(ids[2], ids[3]));
+        assert_eq!(
+            depths_of(&dag),
+            vec![(ids[0], 0), (ids[1], 1), (ids[2], 2), (ids[3], 1)]
+        );
+
+        dag.remove_node(ids[1]);
+        assert_eq!(depths_of(&dag), vec![(ids[0], 0), (ids[2], 0), (ids[3], 1)]);
+        assert_eq!(dag.parents(ids[3]), vec![ids[0]]);
+        assert_eq!(depths_of(&before)[3], (ids[3], 3));
+
+        dag.add_edge(ids[3], ids[2]).unwrap();
+        assert_eq!(depths_of(&dag), vec![(ids[0], 0), (ids[2], 2), (ids[3], 1)]);
+    }
+}
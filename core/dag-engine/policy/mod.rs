@@ -0,0 +1,209 @@
+// core/dag-engine/policy/mod.rs
+
+use std::sync::Arc;
+
+/// Depth bound policy for a DAG, fixed at construction
+pub trait DepthPolicy: Send + Sync {
+    /// Largest root-to-node depth allowed for `node_count` nodes
+    fn bound(&self, node_count: usize) -> usize;
+
+    /// Reject violating inserts (`true`) or accept them and only report
+    fn enforce(&self) -> bool {
+        true
+    }
+
+    /// Serializable description of this policy; `Custom` when it has none
+    fn spec(&self) -> PolicySpec {
+        PolicySpec::Custom
+    }
+}
+
+/// Data form of the built-in policies, stored alongside a serialized DAG
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "snake_case"))]
+pub enum PolicySpec {
+    Log2 {
+        factor: usize,
+        minimum: usize,
+    },
+    FixedCap {
+        cap: usize,
+    },
+    ReportOnly(Box<PolicySpec>),
+    /// Caller-supplied policy (e.g. `CustomBound`) that cannot be stored
+    Custom,
+}
+
+impl PolicySpec {
+    /// Rebuild the policy, or `None` for `Custom`
+    pub fn build(&self) -> Option<Arc<dyn DepthPolicy>> {
+        Some(match self {
+            PolicySpec::Log2 { factor, minimum } => Arc::new(Log2 {
+                factor: *factor,
+                minimum: *minimum,
+            }),
+            PolicySpec::FixedCap { cap } => Arc::new(FixedCap(*cap)),
+            PolicySpec::ReportOnly(inner) => Arc::new(ReportOnly(inner.build()?)),
+            PolicySpec::Custom => return None,
+        })
+    }
+}
+
+impl DepthPolicy for Arc<dyn DepthPolicy> {
+    fn bound(&self, node_count: usize) -> usize {
+        (**self).bound(node_count)
+    }
+
+    fn enforce(&self) -> bool {
+        (**self).enforce()
+    }
+
+    fn spec(&self) -> PolicySpec {
+        (**self).spec()
+    }
+}
+
+/// O(log n) bound: `max(minimum, factor · ⌈log2 n⌉)`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Log2 {
+    pub factor: usize,
+    pub minimum: usize,
+}
+
+impl Default for Log2 {
+    /// `⌈log2 n⌉`, but never below 1 so a two-node chain is always legal
+    fn default() -> Self {
+        Self {
+            factor: 1,
+            minimum: 1,
+        }
+    }
+}
+
+impl DepthPolicy for Log2 {
+    fn bound(&self, node_count: usize) -> usize {
+        let log2 = match node_count {
+            0 | 1 => 0,
+            n => (usize::BITS - (n - 1).leading_zeros()) as usize,
+        };
+        self.minimum.max(self.factor.saturating_mul(log2))
+    }
+
+    fn spec(&self) -> PolicySpec {
+        PolicySpec::Log2 {
+            factor: self.factor,
+            minimum: self.minimum,
+        }
+    }
+}
+
+/// Constant depth cap regardless of node count
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct FixedCap(pub usize);
+
+impl DepthPolicy for FixedCap {
+    fn bound(&self, _node_count: usize) -> usize {
+        self.0
+    }
+
+    fn spec(&self) -> PolicySpec {
+        PolicySpec::FixedCap { cap: self.0 }
+    }
+}
+
+/// Bound computed by a caller-supplied function of the node count
+pub struct CustomBound<F>(pub F);
+
+impl<F> DepthPolicy for CustomBound<F>
+where
+    F: Fn(usize) -> usize + Send + Sync,
+{
+    fn bound(&self, node_count: usize) -> usize {
+        (self.0)(node_count)
+    }
+}
+
+/// Unbounded, but violations of the wrapped policy are recorded on the DAG
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct ReportOnly<P>(pub P);
+
+impl<P: DepthPolicy> DepthPolicy for ReportOnly<P> {
+    fn bound(&self, node_count: usize) -> usize {
+        self.0.bound(node_count)
+    }
+
+    fn enforce(&self) -> bool {
+        false
+    }
+
+    fn spec(&self) -> PolicySpec {
+        PolicySpec::ReportOnly(Box::new(self.0.spec()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::DAG;
+
+    #[test]
+    fn log2_is_ceiling_log_with_factor_and_minimum() {
+        let log2 = Log2::default();
+        let bounds: Vec<usize> = [0, 1, 2, 3, 4, 5, 16, 17].map(|n| log2.bound(n)).to_vec();
+        assert_eq!(bounds, vec![1, 1, 1, 2, 2, 3, 4, 5]);
+
+        let wide = Log2 {
+            factor: 2,
+            minimum: 3,
+        };
+        assert_eq!(wide.bound(2), 3);
+        assert_eq!(wide.bound(16), 8);
+    }
+
+    #[test]
+    fn fixed_and_custom_bounds_ignore_or_use_the_count() {
+        assert_eq!(FixedCap(3).bound(1_000), 3);
+        assert_eq!(CustomBound(|n: usize| n / 2).bound(10), 5);
+        assert!(FixedCap(3).enforce());
+    }
+
+    #[test]
+    fn enforcing_policy_rejects_and_report_only_records() {
+        let mut strict = DAG::with_policy(FixedCap(1));
+        let a = strict.add_node(()).unwrap();
+        let b = strict.add_node(()).unwrap();
+        let c = strict.add_node(()).unwrap();
+        strict.add_edge(a, b).unwrap();
+        assert!(strict.add_edge(b, c).is_err());
+        assert!(strict.reported_violations().is_empty());
+
+        let mut lenient = DAG::with_policy(ReportOnly(FixedCap(1)));
+        let a = lenient.add_node(()).unwrap();
+        let b = lenient.add_node(()).unwrap();
+        let c = lenient.add_node(()).unwrap();
+        lenient.add_edge(a, b).unwrap();
+        lenient.add_edge(b, c).unwrap();
+        assert_eq!(lenient.max_depth(), 2);
+        assert_eq!(lenient.reported_violations().len(), 1);
+    }
+
+    #[test]
+    fn specs_rebuild_the_same_policy() {
+        let policies: Vec<Arc<dyn DepthPolicy>> = vec![
+            Arc::new(Log2 {
+                factor: 2,
+                minimum: 1,
+            }),
+            Arc::new(FixedCap(4)),
+            Arc::new(ReportOnly(Log2::default())),
+        ];
+        for policy in policies {
+            let rebuilt = policy.spec().build().unwrap();
+            assert_eq!(rebuilt.spec(), policy.spec());
+            assert_eq!(rebuilt.enforce(), policy.enforce());
+            assert_eq!(rebuilt.bound(100), policy.bound(100));
+        }
+        assert!(CustomBound(|n: usize| n).spec().build().is_none());
+    }
+}
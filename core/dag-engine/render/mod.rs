@@ -0,0 +1,303 @@
+// core/dag-engine/render/mod.rs
+
+use std::fmt::Write;
+
+use super::{AuxSpaceViolation, NodeId, DAG};
+
+/// Per-node appearance chosen by the caller of [`DAG::to_dot`] / [`DAG::to_mermaid`]
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct NodeStyle {
+    pub label: String,
+    /// Fill colour, as a colour name or `#rrggbb`
+    pub fill: Option<String>,
+    /// Draw a heavy red outline (e.g. a node that reported a fault)
+    pub highlight: bool,
+}
+
+impl NodeStyle {
+    /// Plain node showing `label`
+    pub fn new(label: impl Into<String>) -> Self {
+        Self {
+            label: label.into(),
+            ..Self::default()
+        }
+    }
+}
+
+const DEPTH_FILL: &str = "#f4cccc";
+const VIOLATION_STROKE: &str = "#cc0000";
+const AUX_SPACE_STROKE: &str = "#e69138";
+
+/// Bound violations found on a node, appended to its label
+#[derive(Default)]
+struct Marks {
+    depth: Option<(usize, usize)>,     // (depth, bound)
+    aux_space: Option<(usize, usize)>, // (stack size, bound)
+}
+
+impl Marks {
+    fn note(&self) -> Option<String> {
+        let mut notes = Vec::new();
+        if let Some((depth, bound)) = self.depth {
+            notes.push(format!("depth {depth} > {bound}"));
+        }
+        if let Some((stack_size, bound)) = self.aux_space {
+            notes.push(format!("stack {stack_size} > {bound}"));
+        }
+        (!notes.is_empty()).then(|| notes.join(", "))
+    }
+}
+
+impl<T> DAG<T> {
+    /// Render as a Graphviz DOT digraph, edges pointing at dependents
+    ///
+    /// Nodes past the depth bound are filled red and the node that overflows
+    /// the traversal stack gets a dashed orange outline; both are annotated
+    /// in their label. `style` is called once per node, in id order.
+    pub fn to_dot<F>(&self, mut style: F) -> String
+    where
+        F: FnMut(NodeId, &T) -> NodeStyle,
+    {
+        let marks = self.violation_marks();
+        let mut out = String::from("digraph dag {\n");
+        out.push_str("    node [shape=box, style=\"rounded,filled\", fillcolor=white];\n");
+
+        for id in self.ids() {
+            let node = style(id, &self[id]);
+            let mark = &marks[id.index()];
+            let mut label = node.label;
+            if let Some(note) = mark.note() {
+                label = format!("{label}\n({note})");
+            }
+
+            let mut attributes = vec![format!("label=\"{}\"", escape_dot(&label))];
+            let fill = node
+                .fill
+                .or_else(|| mark.depth.map(|_| DEPTH_FILL.to_string()));
+            if let Some(fill) = fill {
+                attributes.push(format!("fillcolor=\"{}\"", escape_dot(&fill)));
+            }
+            if node.highlight || mark.depth.is_some() {
+                attributes.push(format!("color=\"{VIOLATION_STROKE}\", penwidth=3"));
+            } else if mark.aux_space.is_some() {
+                attributes.push(format!(
+                    "color=\"{AUX_SPACE_STROKE}\", penwidth=2, style=\"rounded,filled,dashed\""
+                ));
+            }
+            let _ = writeln!(out, "    {id} [{}];", attributes.join(", "));
+        }
+
+        for id in self.ids() {
+            for child in self.children(id) {
+                let _ = writeln!(out, "    {id} -> {child};");
+            }
+        }
+
+        out.push_str("}\n");
+        out
+    }
+
+    /// Render as a Mermaid flowchart, highlighting violations like `to_dot`
+    pub fn to_mermaid<F>(&self, mut style: F) -> String
+    where
+        F: FnMut(NodeId, &T) -> NodeStyle,
+    {
+        let marks = self.violation_marks();
+        let mut out = String::from("flowchart TD\n");
+        let (mut depth, mut aux_space, mut highlight) = (Vec::new(), Vec::new(), Vec::new());
+
+        for id in self.ids() {
+            let node = style(id, &self[id]);
+            let mark = &marks[id.index()];
+            let mut label = escape_mermaid(&node.label);
+            if let Some(note) = mark.note() {
+                label = format!("{label}<br/>({note})");
+            }
+
+            let _ = writeln!(out, "    {id}[\"{label}\"]");
+            if let Some(fill) = node.fill {
+                let _ = writeln!(out, "    style {id} fill:{fill}");
+            }
+            if mark.depth.is_some() {
+                depth.push(id.to_string());
+            }
+            if mark.aux_space.is_some() {
+                aux_space.push(id.to_string());
+            }
+            if node.highlight {
+                highlight.push(id.to_string());
+            }
+        }
+
+        for id in self.ids() {
+            for child in self.children(id) {
+                let _ = writeln!(out, "    {id} --> {child}");
+            }
+        }
+
+        let _ = writeln!(
+            out,
+            "    classDef depth fill:{DEPTH_FILL},stroke:{VIOLATION_STROKE},stroke-width:3px"
+        );
+        let _ = writeln!(
+            out,
+            "    classDef auxspace stroke:{AUX_SPACE_STROKE},stroke-width:2px,stroke-dasharray:5 5"
+        );
+        let _ = writeln!(
+            out,
+            "    classDef highlight stroke:{VIOLATION_STROKE},stroke-width:3px"
+        );
+        for (class, names) in [
+            ("depth", depth),
+            ("auxspace", aux_space),
+            ("highlight", highlight),
+        ] {
+            if !names.is_empty() {
+                let _ = writeln!(out, "    class {} {class}", names.join(","));
+            }
+        }
+
+        out
+    }
+
+    /// Depth and aux-space violations per slot index
+    ///
+    /// Depth comes from the depth report; the aux-space mark is the node at
+    /// which a dry-run traversal overflows its stack, if any.
+    fn violation_marks(&self) -> Vec<Marks> {
+        let mut marks: Vec<Marks> = (0..self.slots.len()).map(|_| Marks::default()).collect();
+
+        let report = self.depth_report();
+        for (id, node) in &report.nodes {
+            if !node.within_bound {
+                marks[id.index()].depth = Some((node.depth, report.bound));
+            }
+        }
+
+        if let Err(AuxSpaceViolation::StackOverflow {
+            node,
+            stack_size,
+            bound,
+            ..
+        }) = self.traverse(|_| {})
+        {
+            marks[node.index()].aux_space = Some((stack_size, bound));
+        }
+
+        marks
+    }
+}
+
+/// Escape a DOT quoted string; newlines become centred line breaks
+fn escape_dot(text: &str) -> String {
+    text.replace('\\', "\\\\")
+        .replace('"', "\\\"")
+        .replace('\n', "\\n")
+}
+
+/// Escape a Mermaid quoted label; newlines become `<br/>`
+fn escape_mermaid(text: &str) -> String {
+    text.replace('"', "#quot;").replace('\n', "<br/>")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::Arc;
+
+    use crate::{FixedCap, ReportOnly};
+
+    /// Chain n0 → n1 → n2 under a bound of 1, reported but not enforced
+    fn over_bound() -> (DAG<&'static str>, Vec<NodeId>) {
+        let mut dag = DAG::with_policy(ReportOnly(FixedCap(1)));
+        let ids: Vec<NodeId> = ["a", "b", "c"]
+            .into_iter()
+            .map(|label| dag.add_node(label).unwrap())
+            .collect();
+        dag.add_edge(ids[0], ids[1]).unwrap();
+        dag.add_edge(ids[1], ids[2]).unwrap();
+        (dag, ids)
+    }
+
+    #[test]
+    fn dot_lists_nodes_and_edges() {
+        let mut dag = DAG::with_policy(FixedCap(4));
+        let a = dag.add_node("say \"hi\"").unwrap();
+        let b = dag.add_node("two\nlines").unwrap();
+        dag.add_edge(a, b).unwrap();
+
+        let dot = dag.to_dot(|_, label| NodeStyle::new(*label));
+        assert!(dot.starts_with("digraph dag {\n"));
+        assert!(dot.contains("    n0 [label=\"say \\\"hi\\\"\"];"));
+        assert!(dot.contains("    n1 [label=\"two\\nlines\"];"));
+        assert!(dot.contains("    n0 -> n1;"));
+        assert!(!dot.contains("penwidth"));
+    }
+
+    #[test]
+    fn dot_marks_nodes_past_the_bound() {
+        let (dag, _) = over_bound();
+        let dot = dag.to_dot(|_, label| NodeStyle::new(*label));
+
+        assert!(dot.contains(&format!(
+            "    n2 [label=\"c\\n(depth 2 > 1)\", fillcolor=\"{DEPTH_FILL}\", color=\"{VIOLATION_STROKE}\", penwidth=3];"
+        )));
+        assert!(dot.contains("    n1 [label=\"b\"];"));
+    }
+
+    #[test]
+    fn caller_style_overrides_fill_and_highlights() {
+        let (dag, ids) = over_bound();
+        let style = |id: NodeId, label: &&str| NodeStyle {
+            label: label.to_string(),
+            fill: (id == ids[2]).then(|| "yellow".to_string()),
+            highlight: id == ids[0],
+        };
+
+        let dot = dag.to_dot(style);
+        assert!(dot.contains("n2 [label=\"c\\n(depth 2 > 1)\", fillcolor=\"yellow\""));
+        assert!(dot.contains(&format!(
+            "n0 [label=\"a\", color=\"{VIOLATION_STROKE}\", penwidth=3];"
+        )));
+
+        let mermaid = dag.to_mermaid(style);
+        assert!(mermaid.contains("    style n2 fill:yellow\n"));
+        assert!(mermaid.contains("    class n0 highlight\n"));
+    }
+
+    #[test]
+    fn mermaid_groups_violations_into_classes() {
+        let (dag, _) = over_bound();
+        let mermaid = dag.to_mermaid(|_, label| NodeStyle::new(*label));
+
+        assert!(mermaid.starts_with("flowchart TD\n"));
+        assert!(mermaid.contains("    n2[\"c<br/>(depth 2 > 1)\"]\n"));
+        assert!(mermaid.contains("    n0 --> n1\n"));
+        assert!(mermaid.contains("    class n2 depth\n"));
+        assert!(!mermaid.contains("class n2 auxspace"));
+    }
+
+    #[test]
+    fn stack_overflow_node_gets_the_aux_space_mark() {
+        // A 5-node chain reloaded under a bound of 3
+        let mut dag = DAG::with_policy(FixedCap(8));
+        let ids: Vec<NodeId> = (0..5).map(|i| dag.add_node(i).unwrap()).collect();
+        for pair in ids.windows(2) {
+            dag.add_edge(pair[0], pair[1]).unwrap();
+        }
+        let dag = DAG::from_slots(dag.slots, Arc::new(FixedCap(3)));
+
+        let mermaid = dag.to_mermaid(|_, i| NodeStyle::new(i.to_string()));
+        assert!(mermaid.contains("    n4[\"4<br/>(depth 4 > 3, stack 5 > 4)\"]\n"));
+        assert!(mermaid.contains("    class n4 auxspace\n"));
+
+        let dot = dag.to_dot(|_, i| NodeStyle::new(i.to_string()));
+        assert!(dot.contains("    n4 [label=\"4\\n(depth 4 > 3, stack 5 > 4)\""));
+    }
+
+    #[test]
+    fn mermaid_escapes_quotes_and_newlines() {
+        assert_eq!(escape_mermaid("a \"b\"\nc"), "a #quot;b#quot;<br/>c");
+        assert_eq!(escape_dot("a\\b"), "a\\\\b");
+    }
+}
@@ -1,21 +1,41 @@
 // integration with HDIS
 
+use std::collections::BTreeSet;
+
+use complexity_validator::{log_budget, AuxSpaceGuard};
+use dag_engine::{NodeId, NodeStyle, Visit, DAG};
 use hdis::{HybridDirectedInstruction, StateAwareness, SelfRepair};
 
 /// Connect functor-framework to HDIS
 pub struct HDISIntegration {
     dag: DAG<Archerion>,
     hdis: HybridDirectedInstruction,
+    faults: BTreeSet<NodeId>, // Archerions whose last `watch` reported a SegmentFault
+    space_budget: Box<dyn Fn(usize) -> usize>, // Bytes each observe/consume/watch may allocate
 }
 
+/// Default per-level allocation allowance: `log_budget(BYTES_PER_LEVEL)`
+const BYTES_PER_LEVEL: usize = 256;
+
 impl HDISIntegration {
     /// Create new HDIS-aware functor system
     pub fn new() -> Self {
         Self {
             dag: DAG::new(),
             hdis: HybridDirectedInstruction::init(),
+            faults: BTreeSet::new(),
+            space_budget: Box::new(log_budget(BYTES_PER_LEVEL)),
         }
     }
+
+    /// Replace the auxiliary-space budget, in bytes for n archerions
+    pub fn with_space_budget<B>(mut self, budget: B) -> Self
+    where
+        B: Fn(usize) -> usize + 'static,
+    {
+        self.space_budget = Box::new(budget);
+        self
+    }
     
     /// Register archerion with HDIS topology
     pub fn register_archerion<A>(&mut self, archerion: A) -> Result<(), TopologyError>
@@ -35,13 +55,63 @@ impl HDISIntegration {
     }
     
     /// Execute entire HDIS topology with O(log n) guarantee
-    pub fn execute_topology(&self) -> Result<(), DegradationError> {
-        self.dag.traverse(|archerion| {
+    ///
+    /// Archerions run in dependency order, so every parent is observed and
+    /// consumed first. Each observe/consume/watch runs under an `AuxSpaceGuard`;
+    /// allocations are only counted when `CountingAlloc` is the global allocator.
+    pub fn execute_topology(&mut self) -> Result<(), DegradationError> {
+        let faults = &mut self.faults;
+        let budget = &*self.space_budget;
+        let n = self.dag.len();
+        self.dag.try_traverse_topological(|id, archerion| {
             // Each archerion maintains O(log n) complexity
-            archerion.observe(/* ... */)?;
-            archerion.consume(/* ... */)?;
-            archerion.watch(/* ... */)?;
-            Ok(())
+            AuxSpaceGuard::scope("observe", n, budget, || archerion.observe(/* ... */))??;
+            AuxSpaceGuard::scope("consume", n, budget, || archerion.consume(/* ... */))??;
+
+            let watched = AuxSpaceGuard::scope("watch", n, budget, || archerion.watch(/* ... */))?;
+            if watched.is_err() {
+                faults.insert(id);
+            } else {
+                faults.remove(&id);
+            }
+            watched?;
+            Ok(Visit::Continue)
         })
     }
+
+    /// Archerions whose last `watch` reported a SegmentFault
+    pub fn faulted(&self) -> &BTreeSet<NodeId> {
+        &self.faults
+    }
+
+    /// Graphviz DOT diagram of the topology; faulted archerions are highlighted
+    pub fn to_dot<F>(&self, style: F) -> String
+    where
+        F: FnMut(NodeId, &Archerion) -> NodeStyle,
+    {
+        self.dag.to_dot(self.fault_style(style))
+    }
+
+    /// Mermaid diagram of the topology; faulted archerions are highlighted
+    pub fn to_mermaid<F>(&self, style: F) -> String
+    where
+        F: FnMut(NodeId, &Archerion) -> NodeStyle,
+    {
+        self.dag.to_mermaid(self.fault_style(style))
+    }
+
+    /// Wrap a style callback so faulted archerions are outlined and labelled
+    fn fault_style<'a, F>(&'a self, mut style: F) -> impl FnMut(NodeId, &Archerion) -> NodeStyle + 'a
+    where
+        F: FnMut(NodeId, &Archerion) -> NodeStyle + 'a,
+    {
+        move |id, archerion| {
+            let mut node = style(id, archerion);
+            if self.faults.contains(&id) {
+                node.label.push_str("\nSegmentFault");
+                node.highlight = true;
+            }
+            node
+        }
+    }
 }
@@ -1,5 +1,9 @@
 // iaas/design-protocol/mod.rs
 
+use std::fmt;
+
+use complexity_validator::Complexity;
+
 /// Design protocol for seamless communication
 pub trait DesignProtocol {
     type Problem: ProblemDomain;
@@ -25,19 +29,177 @@ pub trait ProblemDomain {
     fn map_to_solution(&self) -> SolutionSpace;
 }
 
-/// Design solution with O(log n) guarantee
+/// Design solution with a declared complexity target
 pub trait DesignSolution {
     /// Get complexity bound
     fn complexity(&self) -> Complexity;
-    
-    /// Must be O(log n)
+
+    /// Declared bound the solution must stay within (O(log n) by default)
+    fn target(&self) -> Complexity {
+        Complexity::LogN
+    }
+
+    /// Auxiliary space bound the solution must stay within (O(log n) by default)
+    fn space_complexity(&self) -> Complexity {
+        Complexity::LogN
+    }
+
+    /// How much faster than the target a solution may be
+    fn tolerance(&self) -> Tolerance {
+        Tolerance::AnyFaster
+    }
+
+    /// Must be at most the declared target, within the tolerance
     fn validate_complexity(&self) -> Result<(), ComplexityViolation> {
-        match self.complexity() {
-            Complexity::LogN => Ok(()),
-            other => Err(ComplexityViolation::Expected {
-                expected: Complexity::LogN,
-                actual: other,
-            }),
+        self.tolerance().check(self.target(), self.complexity())
+    }
+}
+
+/// Accepted range below a declared target
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Tolerance {
+    /// Any bound at or below the target
+    AnyFaster,
+    /// Exactly the target; anything faster is suspicious
+    Exact,
+    /// Between `floor` and the target, both inclusive
+    AtLeast(Complexity),
+}
+
+impl Tolerance {
+    /// Classify `actual` against `target` in the lattice order
+    ///
+    /// A bound incomparable with the target (e.g. O(V) against O(E)) is not
+    /// provably within it and counts as too slow.
+    pub fn check(
+        &self,
+        target: Complexity,
+        actual: Complexity,
+    ) -> Result<(), ComplexityViolation> {
+        if !actual.is_at_most(&target) {
+            return Err(ComplexityViolation::TooSlow { target, actual });
+        }
+
+        let floor = match self {
+            Tolerance::AnyFaster => return Ok(()),
+            Tolerance::Exact => target.clone(),
+            Tolerance::AtLeast(floor) => floor.clone(),
+        };
+        if floor.is_at_most(&actual) {
+            return Ok(());
+        }
+        Err(ComplexityViolation::UnexpectedlyFaster {
+            target,
+            actual,
+            floor,
+        })
+    }
+}
+
+/// Declared complexity target not met
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ComplexityViolation {
+    /// Solution grows faster than the target, or is incomparable to it
+    TooSlow {
+        target: Complexity,
+        actual: Complexity,
+    },
+    /// Solution is below the tolerated floor: check the cost model
+    UnexpectedlyFaster {
+        target: Complexity,
+        actual: Complexity,
+        floor: Complexity,
+    },
+}
+
+impl fmt::Display for ComplexityViolation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ComplexityViolation::TooSlow { target, actual } => {
+                write!(f, "{actual} exceeds the declared bound {target}")
+            }
+            ComplexityViolation::UnexpectedlyFaster {
+                target,
+                actual,
+                floor,
+            } => write!(
+                f,
+                "{actual} is below the tolerated floor {floor} for target {target}; check your model"
+            ),
+        }
+    }
+}
+
+impl std::error::Error for ComplexityViolation {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Solution declaring `complexity` under the default target and tolerance
+    struct Declared(Complexity);
+
+    impl DesignSolution for Declared {
+        fn complexity(&self) -> Complexity {
+            self.0.clone()
         }
     }
+
+    #[test]
+    fn any_faster_accepts_everything_up_to_the_target() {
+        let tolerance = Tolerance::AnyFaster;
+        assert_eq!(tolerance.check(Complexity::LogN, Complexity::Constant), Ok(()));
+        assert_eq!(tolerance.check(Complexity::LogN, Complexity::LogN), Ok(()));
+        assert_eq!(
+            tolerance.check(Complexity::LogN, Complexity::Linear),
+            Err(ComplexityViolation::TooSlow {
+                target: Complexity::LogN,
+                actual: Complexity::Linear,
+            })
+        );
+    }
+
+    #[test]
+    fn exact_rejects_faster_bounds() {
+        let tolerance = Tolerance::Exact;
+        assert_eq!(tolerance.check(Complexity::Linear, Complexity::Linear), Ok(()));
+        assert_eq!(
+            tolerance.check(Complexity::Linear, Complexity::LogN),
+            Err(ComplexityViolation::UnexpectedlyFaster {
+                target: Complexity::Linear,
+                actual: Complexity::LogN,
+                floor: Complexity::Linear,
+            })
+        );
+    }
+
+    #[test]
+    fn at_least_accepts_the_band_between_floor_and_target() {
+        let tolerance = Tolerance::AtLeast(Complexity::SqrtN);
+        let target = Complexity::NLogN;
+        assert_eq!(tolerance.check(target.clone(), Complexity::SqrtN), Ok(()));
+        assert_eq!(tolerance.check(target.clone(), Complexity::Linear), Ok(()));
+        assert!(matches!(
+            tolerance.check(target.clone(), Complexity::LogN),
+            Err(ComplexityViolation::UnexpectedlyFaster { .. })
+        ));
+        assert!(matches!(
+            tolerance.check(target, Complexity::Polynomial(2)),
+            Err(ComplexityViolation::TooSlow { .. })
+        ));
+    }
+
+    #[test]
+    fn incomparable_bounds_count_as_too_slow() {
+        let (v, e) = (Complexity::var("V").unwrap(), Complexity::var("E").unwrap());
+        let result = Tolerance::AnyFaster.check(v, e);
+        assert!(matches!(result, Err(ComplexityViolation::TooSlow { .. })));
+    }
+
+    #[test]
+    fn default_target_is_log_n() {
+        assert_eq!(Declared(Complexity::LogN).validate_complexity(), Ok(()));
+        assert_eq!(Declared(Complexity::Constant).validate_complexity(), Ok(()));
+        assert!(Declared(Complexity::Linear).validate_complexity().is_err());
+    }
 }